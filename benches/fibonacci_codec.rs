@@ -9,15 +9,21 @@ use rand::{
 
 use criterion::{black_box, BatchSize, Criterion, Throughput};
 
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use common::{U48, U48_MAX};
+
 #[derive(Debug)]
 enum Width {
     U8,
     U16,
     U32,
     U64,
+    U128,
 }
 
-const ALL: &'static [Width; 4] = &[Width::U8, Width::U16, Width::U32, Width::U64];
+const ALL: &[Width; 5] = &[Width::U8, Width::U16, Width::U32, Width::U64, Width::U128];
 const ELTS: usize = 500;
 
 fn encode_multiple_benchmark(c: &mut Criterion) {
@@ -43,6 +49,74 @@ fn encode_multiple_benchmark(c: &mut Criterion) {
             Width::U16 => logic!(b, u16, thread_rng),
             Width::U32 => logic!(b, u32, thread_rng),
             Width::U64 => logic!(b, u64, thread_rng),
+            Width::U128 => logic!(b, u128, thread_rng),
+        });
+    }
+    group.finish();
+}
+
+fn encode_multiple_bytes_benchmark(c: &mut Criterion) {
+    macro_rules! logic {
+        ($b:expr, $t:ty, $rng:expr) => {{
+            $b.iter_batched(
+                || {
+                    let range = Uniform::new(1 as $t, <$t>::MAX);
+                    range.sample_iter(&mut $rng).take(ELTS).collect::<Vec<$t>>()
+                },
+                |v| black_box(v.fib_encode_bytes().expect("should encode right")),
+                BatchSize::LargeInput,
+            )
+        }};
+    }
+
+    let mut group = c.benchmark_group("encode_multiple_bytes");
+    let mut thread_rng = thread_rng();
+    for n in ALL {
+        group.throughput(Throughput::Elements(ELTS as u64));
+        group.bench_with_input(format!("{:?}", n), n, |b, ref n| match n {
+            Width::U8 => logic!(b, u8, thread_rng),
+            Width::U16 => logic!(b, u16, thread_rng),
+            Width::U32 => logic!(b, u32, thread_rng),
+            Width::U64 => logic!(b, u64, thread_rng),
+            Width::U128 => logic!(b, u128, thread_rng),
+        });
+    }
+    group.finish();
+}
+
+/// Compares building the code words of `u64` values in a register
+/// with computing them from the fibonacci table, as [`U48`] does, for
+/// values up to `u32::MAX` and up to [`U48_MAX`].
+fn encode_word_or_table_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_word_or_table");
+    let mut thread_rng = thread_rng();
+    for (width, max) in [("U32", u32::MAX as u64), ("U48", U48_MAX)] {
+        let range = Uniform::new(1, max);
+        group.throughput(Throughput::Elements(ELTS as u64));
+        group.bench_function(format!("{}/word", width), |b| {
+            b.iter_batched(
+                || {
+                    range
+                        .sample_iter(&mut thread_rng)
+                        .take(ELTS)
+                        .collect::<Vec<u64>>()
+                },
+                |v| black_box(v.fib_encode_bytes().expect("should encode right")),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(format!("{}/table", width), |b| {
+            b.iter_batched(
+                || {
+                    range
+                        .sample_iter(&mut thread_rng)
+                        .take(ELTS)
+                        .map(U48)
+                        .collect::<Vec<U48>>()
+                },
+                |v| black_box(v.fib_encode_bytes().expect("should encode right")),
+                BatchSize::LargeInput,
+            )
         });
     }
     group.finish();
@@ -73,6 +147,79 @@ fn decode_multiple_benchmark(c: &mut Criterion) {
                 Width::U16 => logic!(b, u16, fib_decode_u16, thread_rng),
                 Width::U32 => logic!(b, u32, fib_decode_u32, thread_rng),
                 Width::U64 => logic!(b, u64, fib_decode_u64, thread_rng),
+                Width::U128 => logic!(b, u128, fib_decode_u128, thread_rng),
+            }
+        });
+    }
+    group.finish();
+}
+
+fn decode_multiple_bytes_benchmark(c: &mut Criterion) {
+    macro_rules! logic {
+        ($b:expr, $t:ty, $rng:expr) => {{
+            $b.iter_batched(
+                || {
+                    let range = Uniform::new(1 as $t, <$t>::MAX);
+                    let v: Vec<$t> = range.sample_iter(&mut $rng).take(ELTS).collect();
+                    v.fib_encode_bytes().expect("should encode right")
+                },
+                |bytes| black_box(FibDecoder::<$t>::new().feed(&bytes).count()),
+                BatchSize::LargeInput,
+            )
+        }};
+    }
+
+    let mut group = c.benchmark_group("decode_multiple_bytes");
+    for n in ALL {
+        group.throughput(Throughput::Elements(ELTS as u64));
+        group.bench_with_input(format!("{:?}", n), n, |b, ref n| {
+            let mut thread_rng = thread_rng();
+            match n {
+                Width::U8 => logic!(b, u8, thread_rng),
+                Width::U16 => logic!(b, u16, thread_rng),
+                Width::U32 => logic!(b, u32, thread_rng),
+                Width::U64 => logic!(b, u64, thread_rng),
+                Width::U128 => logic!(b, u128, thread_rng),
+            }
+        });
+    }
+    group.finish();
+}
+
+fn decode_multiple_words_benchmark(c: &mut Criterion) {
+    macro_rules! logic {
+        ($b:expr, $t:ty, $rng:expr) => {{
+            $b.iter_batched(
+                || {
+                    let range = Uniform::new(1 as $t, <$t>::MAX);
+                    let v: Vec<$t> = range.sample_iter(&mut $rng).take(ELTS).collect();
+                    let bytes = v.fib_encode_bytes().expect("should encode right");
+                    bytes
+                        .chunks(8)
+                        .map(|chunk| {
+                            let mut word = [0; 8];
+                            word[..chunk.len()].copy_from_slice(chunk);
+                            u64::from_be_bytes(word)
+                        })
+                        .collect::<Vec<u64>>()
+                },
+                |words| black_box(FibDecoder::<$t>::new().feed_words(&words).count()),
+                BatchSize::LargeInput,
+            )
+        }};
+    }
+
+    let mut group = c.benchmark_group("decode_multiple_words");
+    for n in ALL {
+        group.throughput(Throughput::Elements(ELTS as u64));
+        group.bench_with_input(format!("{:?}", n), n, |b, ref n| {
+            let mut thread_rng = thread_rng();
+            match n {
+                Width::U8 => logic!(b, u8, thread_rng),
+                Width::U16 => logic!(b, u16, thread_rng),
+                Width::U32 => logic!(b, u32, thread_rng),
+                Width::U64 => logic!(b, u64, thread_rng),
+                Width::U128 => logic!(b, u128, thread_rng),
             }
         });
     }
@@ -98,6 +245,7 @@ fn encode_1_benchmark(c: &mut Criterion) {
             Width::U16 => logic!(b, u16, thread_rng),
             Width::U32 => logic!(b, u32, thread_rng),
             Width::U64 => logic!(b, u64, thread_rng),
+            Width::U128 => logic!(b, u128, thread_rng),
         });
     }
     group.finish();
@@ -123,6 +271,7 @@ fn decode_1_benchmark(c: &mut Criterion) {
             Width::U16 => logic!(b, u16, fib_decode_u16, thread_rng),
             Width::U32 => logic!(b, u32, fib_decode_u32, thread_rng),
             Width::U64 => logic!(b, u64, fib_decode_u64, thread_rng),
+            Width::U128 => logic!(b, u128, fib_decode_u128, thread_rng),
         });
     }
     group.finish();
@@ -131,7 +280,11 @@ fn decode_1_benchmark(c: &mut Criterion) {
 criterion_group!(
     benches,
     encode_multiple_benchmark,
+    encode_multiple_bytes_benchmark,
+    encode_word_or_table_benchmark,
     decode_multiple_benchmark,
+    decode_multiple_bytes_benchmark,
+    decode_multiple_words_benchmark,
     encode_1_benchmark,
     decode_1_benchmark,
 );
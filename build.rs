@@ -21,20 +21,41 @@ where
 
 const PREAMBLE: &str = r#""#;
 
-fn write_out<T>(out: &mut dyn Write, t_name: &str)
+fn write_out<T>(out: &mut dyn Write, t_name: &str, nonzero_name: &str)
 where
     T: Integer + One + CheckedAdd + Debug + Copy,
 {
     let ints = generate::<T>();
     writeln!(
         out,
-        "impl_fib_encode_for_integral_type!({}, {:?}, DecodeIter, fib_decode_{}, {:?});",
-        t_name, t_name, t_name, ints,
+        "impl_fib_encode_for_integral_type!({}, {:?}, DecodeIter, fib_decode_{}, DecodeWithZeroIter, {}, NonZeroDecodeIter, {:?});",
+        t_name, t_name, t_name, nonzero_name, ints,
     )
     .unwrap();
 }
 
-fn write_decode_wrapper(out: &mut dyn Write, t_names: Vec<&'_ str>) -> Result<(), std::io::Error> {
+fn write_signed_out<U>(out: &mut dyn Write, t_name: &str, unsigned_name: &str)
+where
+    U: Integer + One + CheckedAdd + Debug + Copy,
+{
+    // The magnitudes of the negafibonacci numbers F(-1), F(-2), ...
+    // are the fibonacci numbers 1, 1, 2, 3, 5, ...
+    let mut negafibs = vec![U::one()];
+    negafibs.extend(generate::<U>());
+    writeln!(
+        out,
+        "impl_fib_encode_for_signed_type!({}, {:?}, {}, DecodeIter, fib_decode_{}, NegafibDecodeIter, {:?});",
+        t_name, t_name, unsigned_name, t_name, negafibs,
+    )
+    .unwrap();
+}
+
+fn write_decode_wrapper(
+    out: &mut dyn Write,
+    t_names: Vec<&'_ str>,
+    unsigned_t_names: Vec<(&'_ str, &'_ str)>,
+    negafib_t_names: Vec<&'_ str>,
+) -> Result<(), std::io::Error> {
     out.write_all(b"\npub(crate) mod funcs {\n")?;
     for typename in t_names.iter() {
         writeln!(
@@ -43,21 +64,223 @@ fn write_decode_wrapper(out: &mut dyn Write, t_names: Vec<&'_ str>) -> Result<()
             typename, typename, typename
         )?;
     }
+    for (typename, nonzero_name) in unsigned_t_names.iter() {
+        writeln!(
+            out,
+            "    impl_zero_decoder!({}, {:?}, fib_decode_{}_with_zero);",
+            typename, typename, typename
+        )?;
+        writeln!(
+            out,
+            "    impl_nonzero_decoder!({}, {}, fib_decode_nonzero_{});",
+            typename, nonzero_name, typename
+        )?;
+    }
+    for typename in negafib_t_names.iter() {
+        writeln!(
+            out,
+            "    impl_negafib_decoder!({}, {:?}, negafib_decode_{});",
+            typename, typename, typename
+        )?;
+    }
     out.write_all(b"}\n")?;
     Ok(())
 }
 
+/// Writes the positions of the terminating bits within each byte,
+/// for the byte's bits read least significant bit first: for each
+/// value of the bit read before the byte, and each byte, a mask with
+/// a bit set for each `1` that ends a code word.
+fn write_terminators(out: &mut dyn Write) -> Result<(), std::io::Error> {
+    out.write_all(b"[\n")?;
+    for last in [false, true].iter() {
+        out.write_all(b"    [")?;
+        for byte in 0..=255_u8 {
+            let mut last = *last;
+            let mut ends = 0_u8;
+            for i in 0..8 {
+                let bit = byte >> i & 1 == 1;
+                if bit && last {
+                    ends |= 1 << i;
+                    last = false;
+                } else {
+                    last = bit;
+                }
+            }
+            write!(out, "{}, ", ends)?;
+        }
+        out.write_all(b"],\n")?;
+    }
+    out.write_all(b"]\n")?;
+    Ok(())
+}
+
+/// Writes the code words of the numbers `0..=max`, each as the bits of
+/// the code word, its first bit in the least significant bit, with its
+/// length in the top byte. 0 has no code word, so its entry is 0.
+fn write_code_words(out: &mut dyn Write, max: u32) -> Result<(), std::io::Error> {
+    let table = generate::<u32>();
+    out.write_all(b"[\n")?;
+    for n in 0..=max {
+        let mut code_word = 0;
+        if n > 0 {
+            let split_pos = table.iter().rposition(|elt| *elt <= n).unwrap();
+            let len = split_pos as u32 + 2;
+            assert!(len <= 24, "code word of {} doesn't fit", n);
+            code_word = len << 24 | 1 << (split_pos + 1);
+            let mut remainder = n;
+            for (i, elt) in table[..=split_pos].iter().enumerate().rev() {
+                if *elt <= remainder {
+                    remainder -= elt;
+                    code_word |= 1 << i;
+                }
+            }
+        }
+        writeln!(out, "    {:#x},", code_word)?;
+    }
+    out.write_all(b"]\n")?;
+    Ok(())
+}
+
+/// The lowest position of the code word digits that
+/// `write_code_word_windows` looks up; the remainder below it is less
+/// than the fibonacci number there, and has its code word in the table
+/// that `write_code_words` writes.
+const FIRST_WINDOW_POS: usize = 11;
+
+/// The number of code word digits that each window holds.
+const WINDOW_WIDTH: usize = 8;
+
+/// Writes the windows of `WINDOW_WIDTH` positions that the digits of
+/// the code words of `u64` values are looked up in, from the lowest
+/// one up.
+///
+/// Within a window starting at position `k`, the digits of a remainder
+/// `r` (less than the fibonacci number at the window's end) are those
+/// of the largest sum of non-adjacent fibonacci numbers of the window
+/// that is `<= r`. Any two such sums are at least the fibonacci number
+/// at `k - 1` apart, so when `r` is divided into buckets by shifting
+/// it right by the largest power of two that's no larger, each bucket
+/// holds at most one of them: a bucket's `low` sum applies below its
+/// `high` one, which applies from there on.
+fn write_code_word_windows(out: &mut dyn Write) -> Result<(), std::io::Error> {
+    let table = generate::<u128>();
+    let len = generate::<u64>().len();
+    out.write_all(b"[\n")?;
+    let mut start = FIRST_WINDOW_POS;
+    while start < len {
+        let end = (start + WINDOW_WIDTH).min(len);
+        let shift = 127 - table[start - 1].leading_zeros();
+        let mut sums: Vec<(u128, u8)> = (0..1_u16 << (end - start))
+            .filter(|digits| digits & digits >> 1 == 0)
+            .map(|digits| {
+                let sum = (0..end - start)
+                    .filter(|i| digits >> i & 1 == 1)
+                    .map(|i| table[start + i])
+                    .sum();
+                (sum, digits as u8)
+            })
+            .collect();
+        sums.sort();
+        let max = (table[end] - 1).min(u64::MAX as u128);
+        writeln!(
+            out,
+            "    Window {{ start: {}, shift: {}, buckets: &[",
+            start, shift
+        )?;
+        for bucket in 0..=max >> shift {
+            let from = bucket << shift;
+            let to = (bucket + 1) << shift;
+            let low = *sums.iter().rev().find(|(sum, _)| *sum <= from).unwrap();
+            let mut inside = sums
+                .iter()
+                .filter(|(sum, _)| *sum > from && *sum < to && *sum <= max);
+            let high = *inside.next().unwrap_or(&low);
+            assert!(inside.next().is_none(), "two sums in one bucket");
+            writeln!(
+                out,
+                "        Bucket {{ low: {:#x}, low_digits: {:#x}, high: {:#x}, high_digits: {:#x} }},",
+                low.0, low.1, high.0, high.1
+            )?;
+        }
+        out.write_all(b"    ] },\n")?;
+        start = end;
+    }
+    out.write_all(b"]\n")?;
+    Ok(())
+}
+
+/// Writes the number of windows that `write_code_word_windows` writes
+/// which hold a digit of any number with the given bit length.
+fn write_window_counts(out: &mut dyn Write) -> Result<(), std::io::Error> {
+    let table = generate::<u128>();
+    let len = generate::<u64>().len();
+    let starts: Vec<usize> = (FIRST_WINDOW_POS..len).step_by(WINDOW_WIDTH).collect();
+    out.write_all(b"[")?;
+    for bits in 0..=64_u32 {
+        let count = starts
+            .iter()
+            .filter(|start| table[**start] < 1 << bits)
+            .count();
+        write!(out, "{}, ", count)?;
+    }
+    out.write_all(b"]\n")?;
+    Ok(())
+}
+
 fn main() {
     let out_dir = env::var_os("OUT_DIR").unwrap();
     let output = Path::new(&out_dir).join("int.rs");
     let mut out = BufWriter::new(File::create(&output).unwrap());
     write!(&mut out, "{}", PREAMBLE).unwrap();
 
-    write_out::<u8>(&mut out, "u8");
-    write_out::<u16>(&mut out, "u16");
-    write_out::<u32>(&mut out, "u32");
-    write_out::<u64>(&mut out, "u64");
-    write_decode_wrapper(&mut out, vec!["u8", "u16", "u32", "u64"]).unwrap();
+    write_out::<u8>(&mut out, "u8", "NonZeroU8");
+    write_out::<u16>(&mut out, "u16", "NonZeroU16");
+    write_out::<u32>(&mut out, "u32", "NonZeroU32");
+    write_out::<u64>(&mut out, "u64", "NonZeroU64");
+    write_out::<u128>(&mut out, "u128", "NonZeroU128");
+    write_signed_out::<u8>(&mut out, "i8", "u8");
+    write_signed_out::<u16>(&mut out, "i16", "u16");
+    write_signed_out::<u32>(&mut out, "i32", "u32");
+    write_signed_out::<u64>(&mut out, "i64", "u64");
+    write_signed_out::<u128>(&mut out, "i128", "u128");
+    write_decode_wrapper(
+        &mut out,
+        vec![
+            "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128",
+        ],
+        vec![
+            ("u8", "NonZeroU8"),
+            ("u16", "NonZeroU16"),
+            ("u32", "NonZeroU32"),
+            ("u64", "NonZeroU64"),
+            ("u128", "NonZeroU128"),
+        ],
+        vec!["i8", "i16", "i32", "i64", "i128"],
+    )
+    .unwrap();
+    out.flush().unwrap();
+
+    let output = Path::new(&out_dir).join("terminators.rs");
+    let mut out = BufWriter::new(File::create(&output).unwrap());
+    write_terminators(&mut out).unwrap();
+    out.flush().unwrap();
+
+    // All u8 values, which covers the remainders below the lowest
+    // window:
+    let output = Path::new(&out_dir).join("code_words.rs");
+    let mut out = BufWriter::new(File::create(&output).unwrap());
+    write_code_words(&mut out, u8::MAX as u32).unwrap();
+    out.flush().unwrap();
+
+    let output = Path::new(&out_dir).join("code_word_windows.rs");
+    let mut out = BufWriter::new(File::create(&output).unwrap());
+    write_code_word_windows(&mut out).unwrap();
+    out.flush().unwrap();
+
+    let output = Path::new(&out_dir).join("window_counts.rs");
+    let mut out = BufWriter::new(File::create(&output).unwrap());
+    write_window_counts(&mut out).unwrap();
     out.flush().unwrap();
 
     println!("cargo:rerun-if-changed=build.rs");
@@ -0,0 +1,204 @@
+use crate::bit_order::BitOrder;
+use crate::decode::DecodeError;
+use crate::encode::EncodeOne;
+use crate::incremental::{FibDecoder, IncrementalDecode};
+use crate::sink::ByteSink;
+use crate::writer::BUFFER_SIZE;
+use failure::Fail;
+use std::io;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Fibonacci-encodes numbers one at a time into a tokio
+/// [`AsyncWrite`]r.
+///
+/// This is the asynchronous counterpart of
+/// [`FibWriter`](crate::FibWriter), and writes the same bytes: the bits
+/// that don't make up a complete byte yet stay buffered until
+/// [`finish`](AsyncFibWriter::finish) pads them with zero bits.
+/// Dropping an `AsyncFibWriter` without calling `finish` loses any
+/// buffered bits.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{AsyncFibWriter, Encode};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut writer = AsyncFibWriter::new(Vec::new());
+/// for n in vec![1_u32, 50, 3003] {
+///     writer.write_value(n).await.unwrap();
+/// }
+/// let bytes = writer.finish().await.unwrap();
+/// assert_eq!(vec![1_u32, 50, 3003].fib_encode().unwrap().to_bytes(), bytes);
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct AsyncFibWriter<W> {
+    inner: W,
+    buffer: ByteSink,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncFibWriter<W> {
+    /// Returns a writer that writes encoded numbers to `inner`.
+    pub fn new(inner: W) -> AsyncFibWriter<W> {
+        AsyncFibWriter::with_bit_order(inner, BitOrder::MsbFirst)
+    }
+
+    /// Returns a writer that writes encoded numbers to `inner`,
+    /// storing their bits in the given order within each byte.
+    pub fn with_bit_order(inner: W, order: BitOrder) -> AsyncFibWriter<W> {
+        AsyncFibWriter {
+            inner,
+            buffer: ByteSink::with_bit_order(order),
+        }
+    }
+
+    /// Fibonacci-encodes `value` into the buffer, first writing all
+    /// complete bytes if enough of them are buffered.
+    ///
+    /// # Errors
+    /// Returns an error of kind
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if `value` can't
+    /// be encoded, or the error that writing to the inner writer
+    /// returned. In both cases, none of the value's bits are buffered,
+    /// so it can be written again.
+    pub async fn write_value<T>(&mut self, value: T) -> io::Result<()>
+    where
+        T: EncodeOne,
+        T::Error: Fail,
+    {
+        if self.buffer.complete_bytes().len() >= BUFFER_SIZE {
+            self.write_complete_bytes().await?;
+        }
+        value
+            .fib_encode_into(&mut self.buffer)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.compat()))
+    }
+
+    /// Writes all complete bytes buffered so far to the inner writer,
+    /// and flushes it. The bits that don't make up a complete byte
+    /// stay buffered.
+    pub async fn flush(&mut self) -> io::Result<()> {
+        self.write_complete_bytes().await?;
+        self.inner.flush().await
+    }
+
+    /// Pads the buffered bits with zero bits to a complete byte,
+    /// writes all buffered bytes and returns the inner writer.
+    pub async fn finish(mut self) -> io::Result<W> {
+        self.buffer.pad_to_byte();
+        self.flush().await?;
+        Ok(self.inner)
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Writes all complete bytes buffered so far to the inner writer.
+    /// The bytes of each successful write are removed from the buffer
+    /// right away, so that they aren't written twice if a later write
+    /// fails, or if the future is dropped.
+    async fn write_complete_bytes(&mut self) -> io::Result<()> {
+        while !self.buffer.complete_bytes().is_empty() {
+            match self.inner.write(self.buffer.complete_bytes()).await {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the buffered bytes",
+                    ));
+                }
+                Ok(n) => self.buffer.consume_bytes(n),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fibonacci-decodes numbers of type `T` from a tokio [`AsyncRead`]er,
+/// reading its bytes as they are needed.
+///
+/// This is the asynchronous counterpart of
+/// [`FibReader`](crate::FibReader), and like it decodes the bytes
+/// with a [`FibDecoder`](crate::FibDecoder), which keeps the state of a
+/// code word that is split across the chunks the reader delivers until
+/// the rest of it arrives.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{AsyncFibReader, Encode};
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let bytes = vec![1_u32, 50, 3003].fib_encode().unwrap().to_bytes();
+/// let mut reader = AsyncFibReader::<_, u32>::new(&bytes[..]);
+/// let mut decoded = vec![];
+/// while let Some(n) = reader.read_value().await.unwrap() {
+///     decoded.push(n.unwrap());
+/// }
+/// assert_eq!(vec![1, 50, 3003], decoded);
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct AsyncFibReader<R, T: IncrementalDecode> {
+    inner: R,
+    buffer: Box<[u8]>,
+    decoder: FibDecoder<T>,
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin, T: IncrementalDecode> AsyncFibReader<R, T> {
+    /// Returns a reader that decodes numbers from the bytes of
+    /// `inner`.
+    pub fn new(inner: R) -> AsyncFibReader<R, T> {
+        AsyncFibReader::with_bit_order(inner, BitOrder::MsbFirst)
+    }
+
+    /// Returns a reader that decodes numbers from the bytes of
+    /// `inner`, reading the bits of each byte in the given order.
+    pub fn with_bit_order(inner: R, order: BitOrder) -> AsyncFibReader<R, T> {
+        AsyncFibReader {
+            inner,
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            decoder: FibDecoder::with_bit_order(order),
+            done: false,
+        }
+    }
+
+    /// Reads and decodes the next number. Returns `Ok(None)` at the end
+    /// of the data, and `Ok(Some(Err(_)))` if a code word couldn't be
+    /// decoded, just like [`DecodeIter`](crate::DecodeIter) does.
+    ///
+    /// # Errors
+    /// Returns the error that reading from the inner reader returned.
+    /// Reading can be retried afterwards.
+    pub async fn read_value(&mut self) -> io::Result<Option<Result<T, DecodeError>>> {
+        loop {
+            if let Some(result) = self.decoder.drain().next() {
+                return Ok(Some(result));
+            }
+            if self.done {
+                return Ok(None);
+            }
+            match self.inner.read(&mut self.buffer).await {
+                Ok(0) => {
+                    self.done = true;
+                    if let Err(e) = self.decoder.finish() {
+                        return Ok(Some(Err(e)));
+                    }
+                }
+                Ok(n) => self.decoder.push_bytes(&self.buffer[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the inner reader. Any numbers decoded from the bytes
+    /// read from it, but not returned yet, are lost, and so is a
+    /// partially decoded code word.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
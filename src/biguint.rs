@@ -0,0 +1,204 @@
+//! Functions and iterators to decode arbitrary-precision `BigUint`
+//! integers.
+//!
+//! Decoding gives up on code words longer than
+//! [`MAX_CODE_WORD_LEN`] bits with a
+//! [`FibonacciElementOverflow`](DecodeError::FibonacciElementOverflow)
+//! error, so that a long run of zero bits in untrusted input can't
+//! take unbounded time. Encoding rejects the numbers whose code
+//! words would be longer with a
+//! [`ValueTooLarge`](EncodeError::ValueTooLarge) error, so everything
+//! it encodes can be decoded again.
+
+use crate::decode::{
+    decode_from, decode_from_minus_one, Decode, DecodeError, TableState, WordState,
+};
+use crate::encode::{
+    push_stream_terminator, ElementEncodeError, Encode, EncodeElement, EncodeError, EncodeOne,
+};
+use crate::incremental::{IncrementalDecode, PushBits};
+use crate::sink::BitSink;
+use crate::source::BitSource;
+use crate::table::FibonacciPair;
+use num::{BigUint, One, Zero};
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::mem;
+
+/// The largest number of bits, not counting the terminating one, that
+/// a `BigUint` code word can have and still be decoded. This allows
+/// numbers with tens of thousands of bits.
+pub const MAX_CODE_WORD_LEN: usize = 1 << 16;
+
+/// Fibonacci-encodes `n`. Rather than keeping a table, this computes
+/// the fibonacci numbers up to the largest one that is `<= n`, and
+/// walks back down by subtracting, keeping only two of them at a time.
+///
+/// Returns a `ValueTooLarge` error, without appending anything, if the
+/// code word would be longer than [`MAX_CODE_WORD_LEN`] bits.
+fn bits_from_pair<S: BitSink>(n: &BigUint, result: &mut S) -> Result<(), EncodeError<BigUint>> {
+    if n.is_zero() {
+        return Err(EncodeError::ValueTooSmall(n.clone()));
+    }
+    let mut index = 0;
+    let mut low = BigUint::one();
+    let mut high = BigUint::from(2u8);
+    while high <= *n {
+        index += 1;
+        if index >= MAX_CODE_WORD_LEN {
+            return Err(EncodeError::ValueTooLarge(n.clone()));
+        }
+        let next = &low + &high;
+        low = mem::replace(&mut high, next);
+    }
+
+    let start = result.len();
+    result.push_zeros(index + 2);
+    result.set_bit(start + index + 1);
+    result.set_bit(start + index);
+    let mut remainder = n - &low;
+    while !remainder.is_zero() {
+        let lower = &high - &low;
+        high = mem::replace(&mut low, lower);
+        index -= 1;
+        if low <= remainder {
+            remainder -= &low;
+            result.set_bit(start + index);
+        }
+    }
+    Ok(())
+}
+
+impl EncodeOne for BigUint {
+    type Error = EncodeError<BigUint>;
+
+    fn fib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), EncodeError<BigUint>> {
+        bits_from_pair(&self, sink)
+    }
+}
+
+impl EncodeElement for BigUint {
+    type Integer = BigUint;
+}
+
+impl EncodeElement for &BigUint {
+    type Integer = BigUint;
+}
+
+impl<T> Encode<BigUint> for T
+where
+    T: IntoIterator + Debug + Send + Sync,
+    T::Item: EncodeElement<Integer = BigUint>,
+{
+    type Error = ElementEncodeError<BigUint>;
+
+    fn fib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), ElementEncodeError<BigUint>> {
+        for (i, elt) in self.into_iter().enumerate() {
+            match bits_from_pair(elt.borrow(), sink) {
+                Ok(_) => {}
+                Err(e) => {
+                    return Err(ElementEncodeError { index: i, error: e });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fib_encode_terminated_into<S: BitSink>(
+        self,
+        sink: &mut S,
+    ) -> Result<(), ElementEncodeError<BigUint>> {
+        for (i, elt) in self.into_iter().enumerate() {
+            let n: &BigUint = elt.borrow();
+            // 0 would be shifted to 1, whose code word is the
+            // terminator:
+            let result = if n.is_zero() {
+                Err(EncodeError::ValueTooSmall(n.clone()))
+            } else {
+                bits_from_pair(&(n + 1u8), sink).map_err(|e| match e {
+                    EncodeError::ValueTooLarge(_) => EncodeError::ValueTooLarge(n.clone()),
+                    _ => EncodeError::Underflow(n.clone()),
+                })
+            };
+            if let Err(e) = result {
+                return Err(ElementEncodeError { index: i, error: e });
+            }
+        }
+        push_stream_terminator(sink);
+        Ok(())
+    }
+}
+
+impl Decode for BigUint {
+    fn fib_decode_next<S>(bits: &mut S) -> Option<Result<BigUint, DecodeError>>
+    where
+        S: BitSource + ?Sized,
+    {
+        decode_from(bits, &mut FibonacciPair::default())
+    }
+
+    fn fib_decode_next_terminated<S>(bits: &mut S) -> Option<Result<BigUint, DecodeError>>
+    where
+        S: BitSource + ?Sized,
+    {
+        decode_from_minus_one(bits, &mut FibonacciPair::default())
+    }
+}
+
+impl IncrementalDecode for BigUint {}
+
+impl PushBits for BigUint {
+    type State = TableState<BigUint, FibonacciPair>;
+
+    fn new_state() -> Self::State {
+        TableState {
+            word: WordState::new(BigUint::zero()),
+            table: FibonacciPair::default(),
+        }
+    }
+
+    fn new_terminated_state() -> Self::State {
+        TableState {
+            word: WordState::new(BigUint::one()),
+            table: FibonacciPair::default(),
+        }
+    }
+
+    fn push_bit(state: &mut Self::State, bit: bool) -> Option<Result<BigUint, DecodeError>> {
+        state.word.push(bit, &mut &mut state.table)
+    }
+
+    fn finish_bits(state: &mut Self::State) -> Result<(), DecodeError> {
+        state.word.finish_stream()
+    }
+}
+
+/// An iterator that yields fibonacci-decoded `BigUint` integers.
+pub struct DecodeIter<I> {
+    orig: I,
+    table: FibonacciPair,
+}
+
+impl<I: BitSource> Iterator for DecodeIter<I> {
+    /// This iterator yields `Ok(BigUint)` for every number it
+    /// decodes, and an error for code words that are longer than
+    /// [`MAX_CODE_WORD_LEN`] bits or cut off at the end of the input.
+    type Item = Result<BigUint, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        decode_from(&mut self.orig, &mut self.table)
+    }
+}
+
+/// Returns an iterator that consumes bits (`bool`) and fibonacci-decodes them
+/// into `BigUint` integers.
+pub fn fib_decode_biguint<T, I>(collection: T) -> DecodeIter<I>
+where
+    T: IntoIterator<Item = bool, IntoIter = I>,
+    I: Iterator<Item = bool>,
+{
+    DecodeIter {
+        orig: collection.into_iter(),
+        table: FibonacciPair::default(),
+    }
+}
@@ -0,0 +1,89 @@
+/// The order in which the bits of encoded numbers are stored within
+/// each byte.
+///
+/// `BitVec::to_bytes` and `BitVec::from_bytes` store the first bit in
+/// the most significant bit of each byte, so that is the default.
+/// Some wire formats store the first bit in the least significant bit
+/// instead.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{fib_decode_bytes_with_order, BitOrder, Encode};
+///
+/// // code words: "11" (1), "011" (2)
+/// let numbers = vec![1_u8, 2];
+/// let msb_first = numbers.clone().fib_encode_bytes_with_order(BitOrder::MsbFirst).unwrap();
+/// assert_eq!(vec![0b1101_1000], msb_first);
+/// let lsb_first = numbers.clone().fib_encode_bytes_with_order(BitOrder::LsbFirst).unwrap();
+/// assert_eq!(vec![0b0001_1011], lsb_first);
+///
+/// let decoded: Vec<u8> = fib_decode_bytes_with_order(&lsb_first, BitOrder::LsbFirst)
+///     .map(|n| n.unwrap())
+///     .collect();
+/// assert_eq!(numbers, decoded);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// The first bit is stored in the most significant bit of a
+    /// byte, like `BitVec::to_bytes` does.
+    #[default]
+    MsbFirst,
+
+    /// The first bit is stored in the least significant bit of a
+    /// byte.
+    LsbFirst,
+}
+
+impl BitOrder {
+    /// Returns the mask that selects the `i`th bit (counting from 0)
+    /// stored in a byte.
+    #[inline]
+    pub(crate) fn mask(self, i: u32) -> u8 {
+        match self {
+            BitOrder::MsbFirst => 0x80 >> i,
+            BitOrder::LsbFirst => 1 << i,
+        }
+    }
+
+    /// Returns the mask that selects the first `n` bits (`n` < 8)
+    /// stored in a byte.
+    #[inline]
+    pub(crate) fn leading_mask(self, n: u32) -> u8 {
+        match self {
+            BitOrder::MsbFirst => !(0xff >> n),
+            BitOrder::LsbFirst => !(0xff << n),
+        }
+    }
+
+    /// Returns `byte` with its bits rearranged so that the first one
+    /// stored is the least significant bit.
+    #[inline]
+    pub(crate) fn lsb_first(self, byte: u8) -> u8 {
+        match self {
+            BitOrder::MsbFirst => byte.reverse_bits(),
+            BitOrder::LsbFirst => byte,
+        }
+    }
+
+    /// Returns `word` with its bits rearranged so that the first one
+    /// stored is the least significant bit.
+    #[inline]
+    pub(crate) fn lsb_first_word(self, word: u64) -> u64 {
+        match self {
+            BitOrder::MsbFirst => word.reverse_bits(),
+            BitOrder::LsbFirst => word,
+        }
+    }
+
+    /// Returns the word that holds the bits of `bytes` in the order
+    /// they are stored in: the first byte's bits are the most
+    /// significant ones if the most significant bit is stored first,
+    /// and the least significant ones otherwise.
+    #[inline]
+    pub(crate) fn word_from_bytes(self, bytes: [u8; 8]) -> u64 {
+        match self {
+            BitOrder::MsbFirst => u64::from_be_bytes(bytes),
+            BitOrder::LsbFirst => u64::from_le_bytes(bytes),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Decoding from the `bitvec` crate's bit slices.
+
+use crate::decode::{fib_decode, Decode, DecodeIter};
+use bitvec::order::BitOrder;
+use bitvec::slice::{BitSlice, BitValIter};
+use bitvec::store::BitStore;
+
+/// Returns an iterator that fibonacci-decodes the bits of a `bitvec`
+/// [`BitSlice`] into integers of type `T`.
+///
+/// The bits are read in the slice's index order, so the order type
+/// parameter `O` determines which bit of each storage element is read
+/// first, just like it determines where
+/// [`fib_encode_into`](crate::EncodeOne::fib_encode_into) stores the
+/// bits when appending to a `bitvec::vec::BitVec<T, O>`.
+///
+/// # Example
+/// ``` rust
+/// use bitvec::prelude::*;
+/// use fibonacci_codec::{fib_decode_bitslice, Encode};
+///
+/// let mut bits: BitVec<u8, Lsb0> = BitVec::new();
+/// vec![1_u32, 50, 3003].fib_encode_into(&mut bits).unwrap();
+/// let decoded: Vec<u32> = fib_decode_bitslice(&bits).map(|n| n.unwrap()).collect();
+/// assert_eq!(vec![1, 50, 3003], decoded);
+/// ```
+pub fn fib_decode_bitslice<T, S, O>(bits: &BitSlice<S, O>) -> DecodeIter<BitValIter<'_, S, O>, T>
+where
+    T: Decode,
+    S: BitStore,
+    O: BitOrder,
+{
+    fib_decode(bits.iter().by_vals())
+}
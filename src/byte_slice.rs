@@ -0,0 +1,129 @@
+use crate::bit_order::BitOrder;
+use crate::decode::{fib_decode, Decode, DecodeIter};
+#[cfg(doc)]
+use crate::incremental::FibDecoder;
+
+/// An iterator over the bits of a byte slice, most significant bit of
+/// each byte first unless constructed with
+/// [`with_bit_order`](ByteBits::with_bit_order).
+///
+/// This is the bit order that `BitVec::to_bytes` and
+/// `BitVec::from_bytes` use, so bytes written from an encoded
+/// `BitVec` can be decoded without converting them back into one.
+/// `B` can be anything that can be viewed as a byte slice, e.g. a
+/// `&[u8]` or a `Vec<u8>`.
+#[derive(Debug, Clone)]
+pub struct ByteBits<B> {
+    bytes: B,
+    pos: usize,
+    current: u8,
+    remaining: u32,
+    order: BitOrder,
+}
+
+impl<B> ByteBits<B>
+where
+    B: AsRef<[u8]>,
+{
+    /// Returns an iterator over the bits of `bytes`, most significant
+    /// bit first.
+    pub fn new(bytes: B) -> ByteBits<B> {
+        ByteBits::with_bit_order(bytes, BitOrder::MsbFirst)
+    }
+
+    /// Returns an iterator over the bits of `bytes`, in the given
+    /// order within each byte.
+    pub fn with_bit_order(bytes: B, order: BitOrder) -> ByteBits<B> {
+        ByteBits {
+            bytes,
+            pos: 0,
+            current: 0,
+            remaining: 0,
+            order,
+        }
+    }
+
+    /// Skips the remaining bits of the current byte, e.g. the padding
+    /// after a stream that was encoded with
+    /// [`fib_encode_terminated`](crate::Encode::fib_encode_terminated)
+    /// and converted to bytes.
+    pub fn skip_to_byte_boundary(&mut self) {
+        self.remaining = 0;
+    }
+}
+
+impl<B> Iterator for ByteBits<B>
+where
+    B: AsRef<[u8]>,
+{
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<bool> {
+        if self.remaining == 0 {
+            self.current = *self.bytes.as_ref().get(self.pos)?;
+            self.pos += 1;
+            self.remaining = 8;
+        }
+        let bit = self.current & self.order.mask(8 - self.remaining) != 0;
+        self.remaining -= 1;
+        Some(bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.bytes.as_ref().len() - self.pos) * 8 + self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
+impl<B> ExactSizeIterator for ByteBits<B> where B: AsRef<[u8]> {}
+
+/// Returns an iterator that fibonacci-decodes the bits of `bytes` into
+/// integers of type `T`.
+///
+/// The bits of each byte are read most significant bit first, the
+/// same order that `BitVec::to_bytes` uses. Zero bits padding the last
+/// byte don't form a complete code word, so they are ignored.
+///
+/// This decodes any [`Decode`] type a bit at a time. A [`FibDecoder`]
+/// decodes primitive integers faster, a byte at a time.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{fib_decode_bytes, Encode};
+///
+/// let bytes = vec![1_u32, 50, 3003].fib_encode().unwrap().to_bytes();
+/// let decoded: Vec<u32> = fib_decode_bytes(&bytes).map(|n| n.unwrap()).collect();
+/// assert_eq!(vec![1, 50, 3003], decoded);
+/// ```
+pub fn fib_decode_bytes<T, B>(bytes: B) -> DecodeIter<ByteBits<B>, T>
+where
+    T: Decode,
+    B: AsRef<[u8]>,
+{
+    fib_decode(ByteBits::new(bytes))
+}
+
+/// Returns an iterator that fibonacci-decodes the bits of `bytes` into
+/// integers of type `T`, reading the bits of each byte in the given
+/// order.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{fib_decode_bytes_with_order, BitOrder, Encode};
+///
+/// let bytes = vec![1_u32, 50, 3003]
+///     .fib_encode_bytes_with_order(BitOrder::LsbFirst)
+///     .unwrap();
+/// let decoded: Vec<u32> = fib_decode_bytes_with_order(&bytes, BitOrder::LsbFirst)
+///     .map(|n| n.unwrap())
+///     .collect();
+/// assert_eq!(vec![1, 50, 3003], decoded);
+/// ```
+pub fn fib_decode_bytes_with_order<T, B>(bytes: B, order: BitOrder) -> DecodeIter<ByteBits<B>, T>
+where
+    T: Decode,
+    B: AsRef<[u8]>,
+{
+    fib_decode(ByteBits::with_bit_order(bytes, order))
+}
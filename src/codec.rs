@@ -0,0 +1,188 @@
+//! A `tokio_util` codec for framed streams of fibonacci-coded batches.
+
+use crate::bit_order::BitOrder;
+use crate::decode::DecodeError;
+use crate::encode::Encode;
+use crate::incremental::IncrementalDecode;
+use crate::sink::ByteSink;
+use bytes::{Buf, BytesMut};
+use failure::Fail;
+use std::fmt::Debug;
+use std::io;
+use std::mem;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// Where a [`FibCodec`] is within the code word it decodes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Position {
+    /// At the start of a code word, which may be the terminator.
+    Start,
+
+    /// After the first bit of a code word, which isn't decoded yet,
+    /// since it may be the first bit of the terminator.
+    Second(bool),
+
+    /// Within a code word that isn't the terminator.
+    Inside,
+}
+
+/// A [`tokio_util::codec`] codec that sends batches of numbers of type
+/// `T` as frames.
+///
+/// Each frame holds one batch, encoded with
+/// [`fib_encode_terminated`](crate::Encode::fib_encode_terminated) and
+/// padded with zero bits to a complete byte, so a frame ends exactly
+/// where its terminator says. Any [`Encode`] batch of `T` (e.g. a
+/// `Vec<T>` or a `&[T]`) can be sent; frames are decoded into a
+/// `Vec<T>`. Code words that are split across the chunks a stream
+/// delivers are decoded once the rest of them arrives.
+///
+/// Encoding errors are reported as [`io::Error`]s of kind
+/// [`InvalidInput`](io::ErrorKind::InvalidInput), decoding errors as
+/// ones of kind [`InvalidData`](io::ErrorKind::InvalidData), each
+/// wrapping the original error. A stream that ends in the middle of a
+/// frame fails with [`UnexpectedEof`](io::ErrorKind::UnexpectedEof).
+///
+/// # Example
+/// ``` rust
+/// use bytes::BytesMut;
+/// use fibonacci_codec::FibCodec;
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = FibCodec::<u32>::new();
+/// let mut buffer = BytesMut::new();
+/// codec.encode(vec![1_u32, 50, 3003], &mut buffer).unwrap();
+/// codec.encode(&[7_u32][..], &mut buffer).unwrap();
+///
+/// assert_eq!(Some(vec![1, 50, 3003]), codec.decode(&mut buffer).unwrap());
+/// assert_eq!(Some(vec![7]), codec.decode(&mut buffer).unwrap());
+/// assert_eq!(None, codec.decode(&mut buffer).unwrap());
+/// ```
+#[derive(Debug)]
+pub struct FibCodec<T: IncrementalDecode> {
+    order: BitOrder,
+    state: T::State,
+    position: Position,
+    skip: u32,
+    frame: Vec<T>,
+}
+
+impl<T: IncrementalDecode> FibCodec<T> {
+    /// Returns a codec that stores bits most significant bit first.
+    pub fn new() -> FibCodec<T> {
+        FibCodec::with_bit_order(BitOrder::MsbFirst)
+    }
+
+    /// Returns a codec that stores bits in the given order within each
+    /// byte.
+    pub fn with_bit_order(order: BitOrder) -> FibCodec<T> {
+        FibCodec {
+            order,
+            state: T::new_terminated_state(),
+            position: Position::Start,
+            skip: 0,
+            frame: Vec::new(),
+        }
+    }
+
+    /// Decodes the next bit of a code word other than the terminator.
+    fn push_bit(&mut self, bit: bool) -> Option<Result<T, DecodeError>> {
+        let result = T::push_bit(&mut self.state, bit);
+        self.position = match result {
+            Some(_) => Position::Start,
+            None => Position::Inside,
+        };
+        result
+    }
+}
+
+impl<T: IncrementalDecode> Default for FibCodec<T> {
+    fn default() -> FibCodec<T> {
+        FibCodec::new()
+    }
+}
+
+impl<T: IncrementalDecode> Decoder for FibCodec<T> {
+    type Item = Vec<T>;
+    type Error = io::Error;
+
+    /// Decodes the bytes of `src` up to the end of the next frame.
+    /// The decoded bytes are removed from `src`, and the state of a
+    /// partially decoded code word is kept for the next call, so no
+    /// byte is decoded twice.
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Vec<T>>> {
+        while let Some(byte) = src.first() {
+            let bits = self.order.lsb_first(*byte);
+            while self.skip < 8 {
+                let bit = bits >> self.skip & 1 == 1;
+                self.skip += 1;
+                let result = match self.position {
+                    Position::Start => {
+                        self.position = Position::Second(bit);
+                        None
+                    }
+                    Position::Second(true) if bit => {
+                        // The terminator, followed by the padding up
+                        // to the end of the byte:
+                        self.position = Position::Start;
+                        self.skip = 0;
+                        src.advance(1);
+                        return Ok(Some(mem::take(&mut self.frame)));
+                    }
+                    Position::Second(first) => {
+                        // The first bit can't end a code word:
+                        self.push_bit(first);
+                        self.push_bit(bit)
+                    }
+                    Position::Inside => self.push_bit(bit),
+                };
+                match result {
+                    Some(Ok(n)) => self.frame.push(n),
+                    Some(Err(e)) => {
+                        self.frame.clear();
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, e.compat()));
+                    }
+                    None => {}
+                }
+            }
+            self.skip = 0;
+            src.advance(1);
+        }
+        Ok(None)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<Vec<T>>> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if self.position == Position::Start && self.frame.is_empty() => Ok(None),
+            None => {
+                // Get ready for a new stream:
+                let _ = T::finish_bits(&mut self.state);
+                self.position = Position::Start;
+                self.frame.clear();
+                Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stream ended in the middle of a frame",
+                ))
+            }
+        }
+    }
+}
+
+impl<T, I> Encoder<I> for FibCodec<T>
+where
+    T: IncrementalDecode + Debug + Send + Sync,
+    I: Encode<T>,
+    I::Error: Fail,
+{
+    type Error = io::Error;
+
+    fn encode(&mut self, batch: I, dst: &mut BytesMut) -> io::Result<()> {
+        let mut sink = ByteSink::with_bit_order(self.order);
+        batch
+            .fib_encode_terminated_into(&mut sink)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.compat()))?;
+        dst.extend_from_slice(sink.as_bytes());
+        Ok(())
+    }
+}
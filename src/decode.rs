@@ -1,5 +1,16 @@
-use num::{CheckedAdd, One, Zero};
+use crate::source::{BitSource, WordBits};
+use crate::table::FibonacciTable;
+use num::{CheckedAdd, CheckedSub, One, Zero};
 use std::fmt::Debug;
+use std::iter;
+use std::marker::PhantomData;
+use std::mem;
+
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+use crate::simd;
+
+#[cfg(doc)]
+use crate::integer::FibonacciInteger;
 
 /// Returned if decoding a number fails. Usually indicates an overflow
 /// of the number being decoded.
@@ -21,16 +32,198 @@ pub enum DecodeError {
         bit_pos
     )]
     ConstructionOverflow { bit_pos: usize },
+
+    /// Indicates that the input ended in the middle of a code word,
+    /// i.e. it was cut off or corrupted. Up to 7 trailing zero bits,
+    /// the padding that `BitVec::to_bytes` adds to the last byte, are
+    /// not reported. `bit_pos` is the position (in the code word) of
+    /// the first pending bit that can't be padding, and
+    /// `bits_pending` the number of bits read since the last complete
+    /// code word.
+    #[fail(
+        display = "input ended in the middle of a code word at bit position {:?}, with {:?} bits pending",
+        bit_pos, bits_pending
+    )]
+    Truncated { bit_pos: usize, bits_pending: usize },
+}
+
+/// The number of zero bits that may follow the last code word, as
+/// padding up to a byte boundary.
+const MAX_PADDING_BITS: usize = 7;
+
+/// Integer types that can be fibonacci-decoded.
+///
+/// This crate implements this trait for all primitive integer types,
+/// the `NonZero*` unsigned integer types and all [`FibonacciInteger`]
+/// types. It allows writing code that decodes integers of any width
+/// using [`fib_decode`].
+pub trait Decode: Sized {
+    /// Decodes the next number from `bits`. Returns `None` if `bits`
+    /// ends before a complete number could be read.
+    fn fib_decode_next<S>(bits: &mut S) -> Option<Result<Self, DecodeError>>
+    where
+        S: BitSource + ?Sized;
+
+    /// Decodes the next number from a stream encoded with
+    /// [`fib_encode_terminated`](crate::Encode::fib_encode_terminated),
+    /// in which each number's code word is that of the next larger
+    /// number. Callers need to check for the terminator before.
+    fn fib_decode_next_terminated<S>(bits: &mut S) -> Option<Result<Self, DecodeError>>
+    where
+        S: BitSource + ?Sized;
+}
+
+/// An iterator that yields fibonacci-decoded integers of type `T`.
+#[derive(Debug)]
+pub struct DecodeIter<I, T> {
+    orig: I,
+    _type: PhantomData<T>,
 }
 
-fn multiplier<T>(bit: bool) -> T
+impl<I, T> Iterator for DecodeIter<I, T>
 where
-    T: Zero + One,
+    I: BitSource,
+    T: Decode,
 {
-    if bit {
-        T::one()
-    } else {
-        T::zero()
+    /// This iterator yields `Ok(T)` when a number could be decoded
+    /// successfully and returns an error otherwise.
+    type Item = Result<T, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        T::fib_decode_next(&mut self.orig)
+    }
+}
+
+/// Returns an iterator that consumes bits (`bool`) and fibonacci-decodes
+/// them into integers of type `T`.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{fib_decode, Decode, Encode};
+///
+/// fn decode_all<T: Decode>(bits: &bit_vec::BitVec) -> Vec<T> {
+///     fib_decode(bits).map(|n| n.unwrap()).collect()
+/// }
+///
+/// let bits = vec![1_u16, 50, 3003].fib_encode().unwrap();
+/// assert_eq!(vec![1_u16, 50, 3003], decode_all::<u16>(&bits));
+/// assert_eq!(vec![1_u64, 50, 3003], decode_all::<u64>(&bits));
+/// ```
+pub fn fib_decode<T, C>(collection: C) -> DecodeIter<C::IntoIter, T>
+where
+    T: Decode,
+    C: IntoIterator<Item = bool>,
+{
+    DecodeIter {
+        orig: collection.into_iter(),
+        _type: PhantomData,
+    }
+}
+
+/// Returns an iterator that fibonacci-decodes the bits of a
+/// [`BitSource`] into integers of type `T`.
+///
+/// Bit iterators are bit sources too, but are usually easier to
+/// decode with [`fib_decode`]; this function is for sources that
+/// aren't iterators. It reads the source a word at a time with
+/// [`next_word`](BitSource::next_word), via [`WordBits`].
+pub fn fib_decode_source<T, S>(source: S) -> DecodeIter<WordBits<S>, T>
+where
+    T: Decode,
+    S: BitSource,
+{
+    DecodeIter {
+        orig: WordBits::new(source),
+        _type: PhantomData,
+    }
+}
+
+/// An iterator that yields fibonacci-decoded integers of type `T` up
+/// to the terminator that
+/// [`fib_encode_terminated`](crate::Encode::fib_encode_terminated)
+/// appends.
+#[derive(Debug)]
+pub struct TerminatedDecodeIter<I, T> {
+    orig: I,
+    terminated: bool,
+    _type: PhantomData<T>,
+}
+
+impl<I, T> TerminatedDecodeIter<I, T> {
+    /// Returns `true` once the iterator has read the terminator. If
+    /// the iterator ended without reading it, the input was cut off.
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    /// Returns the underlying bit iterator. Once the terminator was
+    /// read, it is positioned right after it, e.g. at the start of
+    /// the next stream.
+    pub fn into_inner(self) -> I {
+        self.orig
+    }
+}
+
+impl<I, T> Iterator for TerminatedDecodeIter<I, T>
+where
+    I: BitSource,
+    T: Decode,
+{
+    /// This iterator yields `Ok(T)` when a number could be decoded
+    /// successfully and returns an error otherwise.
+    type Item = Result<T, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.terminated {
+            return None;
+        }
+        // The terminator is the code word "11"; other code words that
+        // start with a 1 continue with a 0:
+        match (self.orig.next_bit()?, self.orig.next_bit()) {
+            (true, Some(true)) => {
+                self.terminated = true;
+                None
+            }
+            (first, Some(second)) => {
+                let orig = &mut self.orig;
+                let mut bits = iter::once(first)
+                    .chain(iter::once(second))
+                    .chain(iter::from_fn(|| orig.next_bit()));
+                T::fib_decode_next_terminated(&mut bits)
+            }
+            (first, None) => T::fib_decode_next_terminated(&mut iter::once(first)),
+        }
+    }
+}
+
+/// Returns an iterator that consumes bits (`bool`) encoded with
+/// [`fib_encode_terminated`](crate::Encode::fib_encode_terminated),
+/// and fibonacci-decodes them into integers of type `T`. The iterator
+/// ends at the terminator, even if more bits follow it.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{fib_decode_terminated, Encode};
+///
+/// let mut bits = vec![1_u32, 50].fib_encode_terminated().unwrap();
+/// bits.extend(vec![3003_u32].fib_encode_terminated().unwrap());
+///
+/// let mut first = fib_decode_terminated::<u32, _>(&bits);
+/// assert_eq!(vec![1, 50], first.by_ref().map(|n| n.unwrap()).collect::<Vec<_>>());
+/// assert!(first.is_terminated());
+///
+/// let second = fib_decode_terminated::<u32, _>(first.into_inner());
+/// assert_eq!(vec![3003], second.map(|n| n.unwrap()).collect::<Vec<_>>());
+/// ```
+pub fn fib_decode_terminated<T, C>(collection: C) -> TerminatedDecodeIter<C::IntoIter, T>
+where
+    T: Decode,
+    C: IntoIterator<Item = bool>,
+{
+    TerminatedDecodeIter {
+        orig: collection.into_iter(),
+        terminated: false,
+        _type: PhantomData,
     }
 }
 
@@ -38,12 +231,33 @@ fn is_terminator(bit: bool, last: bool) -> bool {
     bit && last
 }
 
-fn consume_overflow<I>(elt: bool, iterator: &mut I)
+/// Returns the result of the input ending after `bits_pending` bits
+/// of an unterminated code word, the first set bit of which was at
+/// `first_set`.
+fn end_of_input<T>(
+    first_set: Option<usize>,
+    bits_pending: usize,
+) -> Option<Result<T, DecodeError>> {
+    match first_set {
+        Some(bit_pos) => Some(Err(DecodeError::Truncated {
+            bit_pos,
+            bits_pending,
+        })),
+        None if bits_pending > MAX_PADDING_BITS => Some(Err(DecodeError::Truncated {
+            bit_pos: MAX_PADDING_BITS,
+            bits_pending,
+        })),
+        // Done with this stream:
+        None => None,
+    }
+}
+
+fn consume_overflow<S>(elt: bool, source: &mut S)
 where
-    I: Iterator<Item = bool>,
+    S: BitSource + ?Sized,
 {
     let mut last = elt;
-    for elt in iterator {
+    while let Some(elt) = source.next_bit() {
         if is_terminator(elt, last) {
             break;
         }
@@ -51,41 +265,385 @@ where
     }
 }
 
-// Can't write the loop as `for elt in iterator` because we use the
-// iterator again later:
-#[cfg_attr(feature = "cargo-clippy", allow(clippy::while_let_on_iterator))]
 #[inline]
-pub(crate) fn decode_from<I, T>(
-    iterator: &mut I,
-    table: &'static [T],
+pub(crate) fn decode_from<S, T, F>(source: &mut S, table: F) -> Option<Result<T, DecodeError>>
+where
+    S: BitSource + ?Sized,
+    T: CheckedAdd + CheckedSub + PartialOrd + Debug + Clone + Zero + One,
+    F: FibonacciTable<T>,
+{
+    decode_offset_from(source, table, T::zero())
+}
+
+/// Decodes the next number `n` from the source and returns `n - 1`,
+/// the inverse of `bits_from_table_plus_one`.
+#[inline]
+pub(crate) fn decode_from_minus_one<S, T, F>(
+    source: &mut S,
+    table: F,
 ) -> Option<Result<T, DecodeError>>
 where
-    I: Iterator<Item = bool>,
-    T: CheckedAdd + PartialOrd + Debug + Copy + Zero + One,
+    S: BitSource + ?Sized,
+    T: CheckedAdd + CheckedSub + PartialOrd + Debug + Clone + Zero + One,
+    F: FibonacciTable<T>,
+{
+    decode_offset_from(source, table, T::one())
+}
+
+/// Decodes the next number `n` from the source and returns
+/// `n - offset`, the inverse of `bits_from_table_plus`.
+#[inline]
+pub(crate) fn decode_offset_from<S, T, F>(
+    source: &mut S,
+    mut table: F,
+    offset: T,
+) -> Option<Result<T, DecodeError>>
+where
+    S: BitSource + ?Sized,
+    T: CheckedAdd + CheckedSub + PartialOrd + Debug + Clone + Zero + One,
+    F: FibonacciTable<T>,
+{
+    let mut state = WordState::new(offset);
+    while let Some(bit) = source.next_bit() {
+        if let Some(result) = state.push(bit, &mut table) {
+            return Some(result);
+        }
+    }
+    state.finish()
+}
+
+/// The state of decoding a code word one bit at a time: the number
+/// accumulated so far, the position of the next bit and whether the
+/// previous bit was set.
+///
+/// Decoding `n - offset` instead of `n` is how the zero-inclusive,
+/// signed and terminated encodings get decoded.
+#[derive(Debug)]
+pub struct WordState<T> {
+    accumulator: T,
+    offset: T,
+    initial_offset: T,
+    index: usize,
+    last: bool,
+    first_set: Option<usize>,
+    error: Option<DecodeError>,
+}
+
+/// A [`WordState`] along with a table that changes as the code word
+/// gets decoded, for types whose fibonacci numbers aren't all
+/// precomputed.
+#[cfg(feature = "bigint")]
+#[derive(Debug)]
+pub struct TableState<T, F> {
+    pub(crate) word: WordState<T>,
+    pub(crate) table: F,
+}
+
+impl<T> WordState<T>
+where
+    T: CheckedAdd + CheckedSub + PartialOrd + Debug + Clone + Zero + One,
+{
+    /// Returns the state at the start of a code word whose number `n`
+    /// gets decoded as `n - offset`.
+    pub(crate) fn new(offset: T) -> WordState<T> {
+        WordState {
+            accumulator: T::zero(),
+            offset: offset.clone(),
+            initial_offset: offset,
+            index: 0,
+            last: false,
+            first_set: None,
+            error: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.accumulator = T::zero();
+        self.offset = self.initial_offset.clone();
+        self.index = 0;
+        self.last = false;
+        self.first_set = None;
+        self.error = None;
+    }
+
+    /// Decodes the next bit of the code word. Returns the result once
+    /// the code word's terminating bit is reached, and gets ready for
+    /// the next code word.
+    #[inline]
+    pub(crate) fn push<F>(&mut self, bit: bool, table: &mut F) -> Option<Result<T, DecodeError>>
+    where
+        F: FibonacciTable<T>,
+    {
+        if is_terminator(bit, self.last) {
+            let result = match self.error.take() {
+                Some(e) => Err(e),
+                // The code word's number is smaller than the offset:
+                None if !self.offset.is_zero() => Err(DecodeError::ConstructionOverflow {
+                    bit_pos: self.index - 1,
+                }),
+                None => Ok(mem::replace(&mut self.accumulator, T::zero())),
+            };
+            self.reset();
+            return Some(result);
+        }
+        self.last = bit;
+        if self.error.is_some() {
+            // Skip the rest of a code word that failed to decode:
+            return None;
+        }
+
+        let i = self.index;
+        if let Some(fib) = table.get(i) {
+            if bit {
+                self.first_set = self.first_set.or(Some(i));
+                match add_element(&self.accumulator, &mut self.offset, fib) {
+                    Some(new_acc) => self.accumulator = new_acc,
+                    None => self.error = Some(DecodeError::ConstructionOverflow { bit_pos: i }),
+                }
+            }
+        } else {
+            self.error = Some(DecodeError::FibonacciElementOverflow { bit_pos: i });
+        }
+        self.index += 1;
+        None
+    }
+
+    /// Decodes the next eight bits, whose first one is the least
+    /// significant bit of `bits`, and passes the result of each code
+    /// word that ends in them to `emit`.
+    ///
+    /// This gives the same results as pushing each bit, but looks up
+    /// where code words end in the byte in a precomputed table, and
+    /// adds up the elements of the set bits in between without
+    /// checking every bit. Only where an element would be missing from
+    /// the table or the sum would overflow does it push bits one at a
+    /// time, to report the exact position of the error.
+    #[inline]
+    pub(crate) fn push_byte<E>(&mut self, bits: u8, table: &[T], mut emit: E)
+    where
+        E: FnMut(Result<T, DecodeError>),
+    {
+        let mut ends = TERMINATORS[self.last as usize][bits as usize];
+        let mut start = 0;
+        while ends != 0 {
+            let end = ends.trailing_zeros();
+            self.push_segment(bits as u64, start, end, table);
+            let result = self.push(true, &mut { table });
+            emit(result.expect("the table marks a terminator"));
+            start = end + 1;
+            ends &= ends - 1;
+        }
+        self.push_segment(bits as u64, start, 8, table);
+    }
+
+    /// Decodes the next 64 bits, whose first one is the least
+    /// significant bit of `bits`, and passes the result of each code
+    /// word that ends in them to `emit`.
+    ///
+    /// Like [`push_byte`](WordState::push_byte), but finds the ends of
+    /// the code words with bit operations on the whole word instead of
+    /// a table lookup per byte.
+    #[inline]
+    pub(crate) fn push_word<E>(&mut self, bits: u64, table: &[T], emit: E)
+    where
+        E: FnMut(Result<T, DecodeError>),
+    {
+        self.push_word_with_pairs(bits, bits & bits << 1, table, emit)
+    }
+
+    /// Decodes the next words like [`push_word`](WordState::push_word)
+    /// does, but with the `simd` feature, finds the set bits that
+    /// follow another set bit in several words at once.
+    #[inline]
+    pub(crate) fn push_words<E>(&mut self, words: &[u64], table: &[T], mut emit: E)
+    where
+        E: FnMut(Result<T, DecodeError>),
+    {
+        #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+        for block in words.chunks(simd::BLOCK_WORDS) {
+            let mut pairs = [0; simd::BLOCK_WORDS];
+            simd::pairs(block, &mut pairs[..block.len()]);
+            for (bits, pairs) in block.iter().zip(&pairs) {
+                self.push_word_with_pairs(*bits, *pairs, table, &mut emit);
+            }
+        }
+        #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+        for bits in words {
+            self.push_word(*bits, table, &mut emit);
+        }
+    }
+
+    /// Decodes the next 64 bits, given the mask of the set bits that
+    /// follow another set bit of the word.
+    #[inline]
+    fn push_word_with_pairs<E>(&mut self, bits: u64, pairs: u64, table: &[T], mut emit: E)
+    where
+        E: FnMut(Result<T, DecodeError>),
+    {
+        // Each of these ends a code word, unless the bit before it
+        // ended one itself:
+        let mut ends = pairs | (bits & self.last as u64);
+        let mut start = 0;
+        while ends != 0 {
+            let end = ends.trailing_zeros();
+            self.push_segment(bits, start, end, table);
+            let result = self.push(true, &mut { table });
+            emit(result.expect("two set bits end a code word"));
+            start = end + 1;
+            ends &= ends - 1;
+            if start < 64 {
+                // The bit after a terminator starts the next code word:
+                ends &= !(1 << start);
+            }
+        }
+        self.push_segment(bits, start, 64, table);
+    }
+
+    /// Decodes the bits `start..end` of `bits`, none of which end a
+    /// code word.
+    #[inline]
+    fn push_segment(&mut self, bits: u64, start: u32, end: u32, table: &[T]) {
+        if start == end {
+            return;
+        }
+        let len = (end - start) as usize;
+        let segment = (bits & (u64::MAX >> (64 - end))) >> start;
+        if self.error.is_none() && self.index + len <= table.len() {
+            let mut accumulator = self.accumulator.clone();
+            let mut offset = self.offset.clone();
+            let mut set = segment;
+            let mut fits = true;
+            while set != 0 {
+                let fib = &table[self.index + set.trailing_zeros() as usize];
+                match add_element(&accumulator, &mut offset, fib) {
+                    Some(sum) => accumulator = sum,
+                    None => {
+                        fits = false;
+                        break;
+                    }
+                }
+                set &= set - 1;
+            }
+            if fits {
+                if segment != 0 {
+                    let first = self.index + segment.trailing_zeros() as usize;
+                    self.first_set = self.first_set.or(Some(first));
+                }
+                self.accumulator = accumulator;
+                self.offset = offset;
+                self.index += len;
+                self.last = segment >> (len - 1) & 1 == 1;
+                return;
+            }
+        }
+        for i in 0..len {
+            let result = self.push(segment >> i & 1 == 1, &mut { table });
+            debug_assert!(result.is_none());
+        }
+    }
+
+    /// Returns the result of the input ending after the bits pushed
+    /// so far, and gets ready for the next code word: `None` if they
+    /// are padding, an error otherwise.
+    pub(crate) fn finish(&mut self) -> Option<Result<T, DecodeError>> {
+        let result = match self.error.take() {
+            Some(e) => Some(Err(e)),
+            None => end_of_input(self.first_set, self.index),
+        };
+        self.reset();
+        result
+    }
+
+    /// Like [`finish`](WordState::finish), but only returns the error.
+    pub(crate) fn finish_stream(&mut self) -> Result<(), DecodeError> {
+        match self.finish() {
+            Some(Err(e)) => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Adds the element `fib` of a set bit to `accumulator`, after
+/// subtracting what is left of `offset` from it.
+#[inline]
+fn add_element<T>(accumulator: &T, offset: &mut T, fib: &T) -> Option<T>
+where
+    T: CheckedAdd + CheckedSub + PartialOrd + Clone + Zero,
+{
+    if offset.is_zero() {
+        accumulator.checked_add(fib)
+    } else if *fib >= *offset {
+        // Subtracting the offset from the first set bits' elements,
+        // instead of from the result, keeps the accumulator from
+        // overflowing on `T::MAX + offset`:
+        let value = fib.clone() - mem::replace(offset, T::zero());
+        accumulator.checked_add(&value)
+    } else {
+        *offset = offset.clone() - fib.clone();
+        Some(accumulator.clone())
+    }
+}
+
+/// For each value of the previous bit and each byte, whose bits are
+/// read least significant bit first, a mask of the bits that end a
+/// code word. Generated by the build script.
+static TERMINATORS: [[u8; 256]; 2] = include!(concat!(env!("OUT_DIR"), "/terminators.rs"));
+
+/// Decodes the next negafibonacci-coded number from the source,
+/// returning its sign and magnitude. The magnitude of positive numbers
+/// may not exceed `max_positive`, that of negative numbers may not
+/// exceed `max_negative`.
+#[inline]
+pub(crate) fn negafib_decode_from<S, T>(
+    source: &mut S,
+    table: &'static [T],
+    max_positive: T,
+    max_negative: T,
+) -> Option<Result<(bool, T), DecodeError>>
+where
+    S: BitSource + ?Sized,
+    T: CheckedAdd + CheckedSub + PartialOrd + Debug + Copy + Zero,
 {
     let mut i = 0;
-    let mut accumulator: T = T::zero();
+    let mut negative = false;
+    let mut magnitude: T = T::zero();
     let mut last = false;
-    while let Some(elt) = iterator.next() {
+    let mut first_set = None;
+    while let Some(elt) = source.next_bit() {
         if is_terminator(elt, last) {
-            return Some(Ok(accumulator));
+            let max = if negative { max_negative } else { max_positive };
+            if magnitude > max {
+                return Some(Err(DecodeError::ConstructionOverflow { bit_pos: i - 1 }));
+            }
+            return Some(Ok((negative, magnitude)));
         }
 
         if let Some(fib) = table.get(i) {
-            let digit = multiplier::<T>(elt) * *fib;
-            if let Some(new_acc) = accumulator.checked_add(&digit) {
-                accumulator = new_acc;
-            } else {
-                consume_overflow(elt, iterator);
-                return Some(Err(DecodeError::ConstructionOverflow { bit_pos: i }));
+            if elt {
+                first_set = first_set.or(Some(i));
+                // Elements at odd positions are negative:
+                let digit_negative = i % 2 == 1;
+                if magnitude.is_zero() || digit_negative == negative {
+                    negative = digit_negative;
+                    if let Some(new_mag) = magnitude.checked_add(fib) {
+                        magnitude = new_mag;
+                    } else {
+                        consume_overflow(elt, source);
+                        return Some(Err(DecodeError::ConstructionOverflow { bit_pos: i }));
+                    }
+                } else if magnitude >= *fib {
+                    magnitude = magnitude - *fib;
+                } else {
+                    negative = !negative;
+                    magnitude = *fib - magnitude;
+                }
             }
         } else {
-            consume_overflow(elt, iterator);
+            consume_overflow(elt, source);
             return Some(Err(DecodeError::FibonacciElementOverflow { bit_pos: i }));
         }
         i += 1;
         last = elt;
     }
-    // Done with this stream:
-    None
+    end_of_input(first_set, i)
 }
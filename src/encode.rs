@@ -1,6 +1,10 @@
+use crate::bit_order::BitOrder;
+use crate::integer::FibonacciInteger;
+use crate::sink::{BitSink, ByteSink};
 use bit_vec::BitVec;
 use failure::Fail;
-use num::CheckedSub;
+use num::{CheckedSub, Zero};
+use std::borrow::Borrow;
 use std::fmt::{Debug, Display, Error, Formatter};
 
 /// Indicates that encoding a number failed.
@@ -13,6 +17,11 @@ where
     /// represented in fibonacci encoding.
     ValueTooSmall(T),
 
+    /// Indicates an attempt to encode a `BigUint` whose code word
+    /// would be longer than `biguint::MAX_CODE_WORD_LEN` bits, which
+    /// decoding doesn't accept.
+    ValueTooLarge(T),
+
     /// A bug in fibonacci_codec in which encoding the contained
     /// number resulted in an attempt to subtract a larger fibonacci
     /// number than the number to encode.
@@ -26,6 +35,7 @@ where
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
         match *self {
             EncodeError::ValueTooSmall(ref n) => write!(f, "value {:?} is too small to encode", n),
+            EncodeError::ValueTooLarge(ref n) => write!(f, "value {:?} is too large to encode", n),
             EncodeError::Underflow(ref n) => {
                 write!(f, "underflow occurred, could not encode {:?}", n)
             }
@@ -63,17 +73,32 @@ where
     }
 }
 
-/// Allows encoding single primitive integers (> 0) using fibonacci
-/// coding.
+/// Allows encoding single primitive integers using fibonacci coding.
+///
+/// Unsigned integers must be greater than 0; signed integers get
+/// zigzag-mapped first, so any signed value can be encoded. The
+/// `NonZero*` types can not hold 0, so encoding them can not fail.
 pub trait EncodeOne
 where
     Self: Sized + Debug + Send + Sync,
 {
+    /// The error returned when encoding fails: [`EncodeError`] for
+    /// primitive integers and `BigUint`, and
+    /// [`Infallible`](std::convert::Infallible) for the `NonZero*`
+    /// types.
+    ///
+    /// Unsigned integers can't encode 0, and a `BigUint` can't encode
+    /// a number whose code word would be longer than
+    /// `biguint::MAX_CODE_WORD_LEN` bits. Signed integers can encode
+    /// any value.
+    type Error;
+
     /// Fibonacci-encodes an integer into a bit vector and returns the
     /// resulting vector.
     /// # Errors
-    /// Returns an error when attempting to encode 0.
-    fn fib_encode(self) -> Result<BitVec, EncodeError<Self>> {
+    /// Returns [`Self::Error`](EncodeOne::Error) if the integer can't
+    /// be encoded, e.g. an unsigned 0.
+    fn fib_encode(self) -> Result<BitVec, Self::Error> {
         let mut vec = BitVec::default();
         self.fib_encode_mut(&mut vec)?;
         Ok(vec)
@@ -83,28 +108,63 @@ where
     /// vector. It extends the bit vector by the numer of bits
     /// required to hold the output.
     /// # Errors
-    /// Returns an error when attempting to encode 0.
-    fn fib_encode_mut(self, vec: &mut BitVec) -> Result<(), EncodeError<Self>>;
+    /// Returns [`Self::Error`](EncodeOne::Error) if the integer can't
+    /// be encoded, e.g. an unsigned 0.
+    fn fib_encode_mut(self, vec: &mut BitVec) -> Result<(), Self::Error> {
+        self.fib_encode_into(vec)
+    }
+
+    /// Fibonacci-encodes an integer into bytes and returns them. The
+    /// result is the same as that of `fib_encode().to_bytes()`.
+    /// # Errors
+    /// Returns [`Self::Error`](EncodeOne::Error) if the integer can't
+    /// be encoded, e.g. an unsigned 0.
+    fn fib_encode_bytes(self) -> Result<Vec<u8>, Self::Error> {
+        self.fib_encode_bytes_with_order(BitOrder::MsbFirst)
+    }
+
+    /// Fibonacci-encodes an integer into bytes, storing the bits in
+    /// the given order within each byte.
+    /// # Errors
+    /// Returns [`Self::Error`](EncodeOne::Error) if the integer can't
+    /// be encoded, e.g. an unsigned 0.
+    fn fib_encode_bytes_with_order(self, order: BitOrder) -> Result<Vec<u8>, Self::Error> {
+        let mut sink = ByteSink::with_bit_order(order);
+        self.fib_encode_into(&mut sink)?;
+        Ok(sink.into_bytes())
+    }
+
+    /// Fibonacci-encodes an integer onto the end of a [`BitSink`].
+    /// # Errors
+    /// Returns [`Self::Error`](EncodeOne::Error) if the integer can't
+    /// be encoded, e.g. an unsigned 0.
+    fn fib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), Self::Error>;
 }
 
-/// Allows encoding enumerations of unsigned integers (> 0) using
-/// fibonacci coding.
+/// Allows encoding enumerations of integers using fibonacci coding.
 ///
 /// This crate implements this trait for anything that is
-/// `IntoIterator` with primitive unsigned integer elements.
+/// `IntoIterator` with primitive integer elements, or references to
+/// them (see [`EncodeElement`]). Signed elements
+/// are zigzag-mapped before encoding.
 ///
 /// ## A note about zero
 /// The number `0` can't be encoded using fibonacci coding. If you
-/// need to encode a zero, you can use `.map(|x| x+1)` before encoding
-/// and invert this when decoding.
+/// need to encode an unsigned zero, use [`EncodeWithZero`] instead.
 pub trait Encode<T>
 where
     Self: Sized + Debug + Send + Sync,
     T: Debug + Send + Sync,
 {
+    /// The error returned when encoding an element fails:
+    /// [`ElementEncodeError`] for primitive integers, and
+    /// [`Infallible`](std::convert::Infallible) for the `NonZero*`
+    /// types.
+    type Error;
+
     /// Fibonacci-encodes an iterator of integers into bits and
     /// returns the resulting bit vector.
-    fn fib_encode(self) -> Result<BitVec, ElementEncodeError<T>> {
+    fn fib_encode(self) -> Result<BitVec, Self::Error> {
         let mut vec = BitVec::default();
         self.fib_encode_mut(&mut vec)?;
         Ok(vec)
@@ -120,28 +180,486 @@ where
     /// `fib_encode_mut` returns an error indicating at which element
     /// the error occurred. It leaves the previous, correctly-encoded
     /// values' bits in the result bit vector.
-    fn fib_encode_mut(self, vec: &mut BitVec) -> Result<(), ElementEncodeError<T>>;
+    fn fib_encode_mut(self, vec: &mut BitVec) -> Result<(), Self::Error> {
+        self.fib_encode_into(vec)
+    }
+
+    /// Fibonacci-encodes an iterator of integers into bytes and
+    /// returns them. The result is the same as that of
+    /// `fib_encode().to_bytes()`.
+    fn fib_encode_bytes(self) -> Result<Vec<u8>, Self::Error> {
+        self.fib_encode_bytes_with_order(BitOrder::MsbFirst)
+    }
+
+    /// Fibonacci-encodes an iterator of integers into bytes, storing
+    /// the bits in the given order within each byte.
+    fn fib_encode_bytes_with_order(self, order: BitOrder) -> Result<Vec<u8>, Self::Error> {
+        let mut sink = ByteSink::with_bit_order(order);
+        self.fib_encode_into(&mut sink)?;
+        Ok(sink.into_bytes())
+    }
+
+    /// Fibonacci-encodes an iterator yielding integers onto the end
+    /// of a [`BitSink`], until the iterator is exhausted. Errors are
+    /// handled like in [`fib_encode_mut`](Encode::fib_encode_mut).
+    fn fib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), Self::Error>;
+
+    /// Fibonacci-encodes an iterator of integers into bits, followed
+    /// by a terminator, and returns the resulting bit vector.
+    ///
+    /// The terminator is the code word "11". To keep it from being
+    /// mistaken for a number, each number's code word is that of the
+    /// next larger one, so the bits differ from those that
+    /// [`fib_encode`](Encode::fib_encode) returns. Decode them with
+    /// [`fib_decode_terminated`](crate::fib_decode_terminated), which
+    /// stops at the terminator even if more bits follow it.
+    fn fib_encode_terminated(self) -> Result<BitVec, Self::Error> {
+        let mut vec = BitVec::default();
+        self.fib_encode_terminated_into(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Fibonacci-encodes an iterator of integers onto the end of a
+    /// [`BitSink`], followed by a terminator; see
+    /// [`fib_encode_terminated`](Encode::fib_encode_terminated). If
+    /// encoding an element fails, no terminator is appended.
+    fn fib_encode_terminated_into<S: BitSink>(self, sink: &mut S) -> Result<(), Self::Error>;
+}
+
+/// An element of a collection that [`Encode`], [`EncodeWithZero`] and
+/// [`NegafibEncode`] accept: an integer, or a reference to one.
+///
+/// The associated `Integer` type tells the encoding traits which
+/// integer type to encode the element as, so that encoding e.g. a
+/// `&[u32]` or a `slice.iter()` doesn't need type annotations.
+///
+/// This crate implements this trait for all integer types it can
+/// encode, and for references to the primitive, `NonZero*` and
+/// `BigUint` integer types. Types implementing [`FibonacciInteger`] get an
+/// implementation for themselves; to encode references to them,
+/// implement it for `&'a YourType` as well.
+///
+/// [`FibonacciInteger`]: crate::FibonacciInteger
+pub trait EncodeElement: Borrow<Self::Integer> {
+    /// The integer type this element gets encoded as.
+    type Integer;
+}
+
+/// Allows encoding single unsigned primitive integers, including 0,
+/// using fibonacci coding.
+///
+/// Each number `n` is encoded as the fibonacci code word for `n + 1`,
+/// without ever representing `n + 1` in the integer type, so the
+/// type's maximum value can be encoded as well.
+pub trait EncodeOneWithZero
+where
+    Self: Sized + Debug + Send + Sync,
+{
+    /// Fibonacci-encodes an integer (offset by one) into a bit vector
+    /// and returns the resulting vector.
+    fn fib_encode_with_zero(self) -> Result<BitVec, EncodeError<Self>> {
+        let mut vec = BitVec::default();
+        self.fib_encode_with_zero_mut(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Fibonacci-encodes an integer (offset by one) onto the end of
+    /// an existing bit vector. It extends the bit vector by the
+    /// number of bits required to hold the output.
+    fn fib_encode_with_zero_mut(self, vec: &mut BitVec) -> Result<(), EncodeError<Self>> {
+        self.fib_encode_with_zero_into(vec)
+    }
+
+    /// Fibonacci-encodes an integer (offset by one) into bytes and
+    /// returns them. The result is the same as that of
+    /// `fib_encode_with_zero().to_bytes()`.
+    fn fib_encode_with_zero_bytes(self) -> Result<Vec<u8>, EncodeError<Self>> {
+        self.fib_encode_with_zero_bytes_with_order(BitOrder::MsbFirst)
+    }
+
+    /// Fibonacci-encodes an integer (offset by one) into bytes,
+    /// storing the bits in the given order within each byte.
+    fn fib_encode_with_zero_bytes_with_order(
+        self,
+        order: BitOrder,
+    ) -> Result<Vec<u8>, EncodeError<Self>> {
+        let mut sink = ByteSink::with_bit_order(order);
+        self.fib_encode_with_zero_into(&mut sink)?;
+        Ok(sink.into_bytes())
+    }
+
+    /// Fibonacci-encodes an integer (offset by one) onto the end of a
+    /// [`BitSink`].
+    fn fib_encode_with_zero_into<S: BitSink>(self, sink: &mut S) -> Result<(), EncodeError<Self>>;
+}
+
+/// Allows encoding enumerations of unsigned integers, including 0,
+/// using fibonacci coding.
+///
+/// This crate implements this trait for anything that is
+/// `IntoIterator` with primitive unsigned integer elements, or
+/// references to them.
+pub trait EncodeWithZero<T>
+where
+    Self: Sized + Debug + Send + Sync,
+    T: Debug + Send + Sync,
+{
+    /// Fibonacci-encodes an iterator of integers (each offset by one)
+    /// into bits and returns the resulting bit vector.
+    fn fib_encode_with_zero(self) -> Result<BitVec, ElementEncodeError<T>> {
+        let mut vec = BitVec::default();
+        self.fib_encode_with_zero_mut(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Fibonacci-encodes an iterator yielding integers (each offset
+    /// by one) onto the end of an existing bit vector, until the
+    /// iterator is exhausted.
+    fn fib_encode_with_zero_mut(self, vec: &mut BitVec) -> Result<(), ElementEncodeError<T>> {
+        self.fib_encode_with_zero_into(vec)
+    }
+
+    /// Fibonacci-encodes an iterator of integers (each offset by one)
+    /// into bytes and returns them. The result is the same as that of
+    /// `fib_encode_with_zero().to_bytes()`.
+    fn fib_encode_with_zero_bytes(self) -> Result<Vec<u8>, ElementEncodeError<T>> {
+        self.fib_encode_with_zero_bytes_with_order(BitOrder::MsbFirst)
+    }
+
+    /// Fibonacci-encodes an iterator of integers (each offset by one)
+    /// into bytes, storing the bits in the given order within each
+    /// byte.
+    fn fib_encode_with_zero_bytes_with_order(
+        self,
+        order: BitOrder,
+    ) -> Result<Vec<u8>, ElementEncodeError<T>> {
+        let mut sink = ByteSink::with_bit_order(order);
+        self.fib_encode_with_zero_into(&mut sink)?;
+        Ok(sink.into_bytes())
+    }
+
+    /// Fibonacci-encodes an iterator yielding integers (each offset
+    /// by one) onto the end of a [`BitSink`], until the iterator is
+    /// exhausted.
+    fn fib_encode_with_zero_into<S: BitSink>(
+        self,
+        sink: &mut S,
+    ) -> Result<(), ElementEncodeError<T>>;
+}
+
+/// Allows encoding single signed primitive integers (other than 0)
+/// using negafibonacci coding.
+///
+/// Negafibonacci coding uses the fibonacci numbers with negative
+/// indexes (1, -1, 2, -3, 5, -8, ...) as its base, so it can
+/// represent negative numbers directly, without a zigzag mapping. Code
+/// words are terminated by "11", just like fibonacci code words.
+pub trait NegafibEncodeOne
+where
+    Self: Sized + Debug + Send + Sync,
+{
+    /// Negafibonacci-encodes an integer into a bit vector and returns
+    /// the resulting vector.
+    /// # Errors
+    /// Returns an error when attempting to encode 0.
+    fn negafib_encode(self) -> Result<BitVec, EncodeError<Self>> {
+        let mut vec = BitVec::default();
+        self.negafib_encode_mut(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Negafibonacci-encodes an integer onto the end of an existing
+    /// bit vector. It extends the bit vector by the number of bits
+    /// required to hold the output.
+    /// # Errors
+    /// Returns an error when attempting to encode 0.
+    fn negafib_encode_mut(self, vec: &mut BitVec) -> Result<(), EncodeError<Self>> {
+        self.negafib_encode_into(vec)
+    }
+
+    /// Negafibonacci-encodes an integer into bytes and returns them.
+    /// The result is the same as that of `negafib_encode().to_bytes()`.
+    /// # Errors
+    /// Returns an error when attempting to encode 0.
+    fn negafib_encode_bytes(self) -> Result<Vec<u8>, EncodeError<Self>> {
+        self.negafib_encode_bytes_with_order(BitOrder::MsbFirst)
+    }
+
+    /// Negafibonacci-encodes an integer into bytes, storing the bits
+    /// in the given order within each byte.
+    /// # Errors
+    /// Returns an error when attempting to encode 0.
+    fn negafib_encode_bytes_with_order(
+        self,
+        order: BitOrder,
+    ) -> Result<Vec<u8>, EncodeError<Self>> {
+        let mut sink = ByteSink::with_bit_order(order);
+        self.negafib_encode_into(&mut sink)?;
+        Ok(sink.into_bytes())
+    }
+
+    /// Negafibonacci-encodes an integer onto the end of a [`BitSink`].
+    /// # Errors
+    /// Returns an error when attempting to encode 0.
+    fn negafib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), EncodeError<Self>>;
+}
+
+/// Allows encoding enumerations of signed integers (other than 0)
+/// using negafibonacci coding.
+///
+/// This crate implements this trait for anything that is
+/// `IntoIterator` with primitive signed integer elements, or
+/// references to them.
+pub trait NegafibEncode<T>
+where
+    Self: Sized + Debug + Send + Sync,
+    T: Debug + Send + Sync,
+{
+    /// Negafibonacci-encodes an iterator of integers into bits and
+    /// returns the resulting bit vector.
+    fn negafib_encode(self) -> Result<BitVec, ElementEncodeError<T>> {
+        let mut vec = BitVec::default();
+        self.negafib_encode_mut(&mut vec)?;
+        Ok(vec)
+    }
+
+    /// Negafibonacci-encodes an iterator yielding integers onto the
+    /// end of an existing bit vector, until the iterator is exhausted.
+    ///
+    /// # Error handling
+    /// When encountering an encoding error at any element,
+    /// `negafib_encode_mut` returns an error indicating at which
+    /// element the error occurred. It leaves the previous,
+    /// correctly-encoded values' bits in the result bit vector.
+    fn negafib_encode_mut(self, vec: &mut BitVec) -> Result<(), ElementEncodeError<T>> {
+        self.negafib_encode_into(vec)
+    }
+
+    /// Negafibonacci-encodes an iterator of integers into bytes and
+    /// returns them. The result is the same as that of
+    /// `negafib_encode().to_bytes()`.
+    fn negafib_encode_bytes(self) -> Result<Vec<u8>, ElementEncodeError<T>> {
+        self.negafib_encode_bytes_with_order(BitOrder::MsbFirst)
+    }
+
+    /// Negafibonacci-encodes an iterator of integers into bytes,
+    /// storing the bits in the given order within each byte.
+    fn negafib_encode_bytes_with_order(
+        self,
+        order: BitOrder,
+    ) -> Result<Vec<u8>, ElementEncodeError<T>> {
+        let mut sink = ByteSink::with_bit_order(order);
+        self.negafib_encode_into(&mut sink)?;
+        Ok(sink.into_bytes())
+    }
+
+    /// Negafibonacci-encodes an iterator yielding integers onto the
+    /// end of a [`BitSink`], until the iterator is exhausted.
+    fn negafib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), ElementEncodeError<T>>;
 }
 
 #[inline]
-pub(crate) fn bits_from_table<T>(
+pub(crate) fn bits_from_table<T, S>(n: T, table: &[T], result: &mut S) -> Result<(), EncodeError<T>>
+where
+    T: CheckedSub + PartialOrd + Debug + Clone + Send + Sync + 'static,
+    S: BitSink,
+{
+    let split_pos = match table.iter().rposition(|elt| elt <= &n) {
+        Some(split_pos) => split_pos,
+        None => return Err(EncodeError::ValueTooSmall(n)),
+    };
+    let remainder = match n.checked_sub(&table[split_pos]) {
+        Some(remainder) => remainder,
+        None => return Err(EncodeError::Underflow(n)),
+    };
+    code_word_from_table(n, split_pos, remainder, table, result)
+}
+
+/// Fibonacci-encodes `n`, building its code word in a register if it
+/// fits in a `u64`.
+#[inline(always)]
+pub(crate) fn bits_from_word_or_table<T, S>(
     n: T,
-    table: &'static [T],
-    result: &mut BitVec,
+    table: &[T],
+    result: &mut S,
 ) -> Result<(), EncodeError<T>>
 where
-    T: CheckedSub + PartialOrd + Debug + Copy + Send + Sync + 'static,
+    T: FibonacciInteger,
+    S: BitSink,
 {
-    let mut current = n;
+    if let Some(word) = n.word_value() {
+        if code_word_from_word(word, 0, result) {
+            return Ok(());
+        }
+    }
+    bits_from_table(n, table, result)
+}
+
+/// The code words of the numbers up to `u8::MAX`, generated by the
+/// build script. Each holds the bits of the code word, its first bit in
+/// the least significant bit, with its length in the top byte; 0 has no
+/// code word, so its entry is 0.
+static CODE_WORDS: [u32; u8::MAX as usize + 1] =
+    include!(concat!(env!("OUT_DIR"), "/code_words.rs"));
+
+/// Appends the code word of `n + offset` all at once, looking it up in
+/// [`CODE_WORDS`] or building it in a register. Returns `false`,
+/// without appending anything, if `n + offset` is 0 or doesn't fit in
+/// a `u64`.
+#[inline(always)]
+fn code_word_from_word<S: BitSink>(n: u64, offset: u64, result: &mut S) -> bool {
+    let n = match n.checked_add(offset) {
+        Some(n) if n > 0 => n,
+        _ => return false,
+    };
+    if n < CODE_WORDS.len() as u64 {
+        let code_word = CODE_WORDS[n as usize];
+        result.push_bits((code_word & 0xff_ffff) as u64, code_word >> 24);
+        return true;
+    }
+    let (code_word, len) = code_word_in_register(n);
+    result.push_bits(code_word as u64, len.min(64));
+    if len > 64 {
+        result.push_bits((code_word >> 64) as u64, len - 64);
+    }
+    true
+}
+
+/// The digits of a code word within a window of its positions.
+struct Window {
+    /// The position of the window's lowest digit.
+    start: u32,
+    /// The shift that turns a remainder into the index of its bucket.
+    shift: u32,
+    buckets: &'static [Bucket],
+}
+
+/// The sums of fibonacci numbers of a window that a range of
+/// remainders can hold: `low` below `high`, `high` from there on.
+struct Bucket {
+    low: u64,
+    low_digits: u8,
+    high: u64,
+    high_digits: u8,
+}
+
+/// The windows that the digits of the code words of `u64` values are
+/// looked up in, from the lowest one up, generated by the build script.
+/// Below the lowest one, the remainder's code word is in [`CODE_WORDS`].
+static WINDOWS: &[Window] = &include!(concat!(env!("OUT_DIR"), "/code_word_windows.rs"));
+
+/// The number of [`WINDOWS`] that hold a digit of any number of a bit
+/// length.
+static WINDOW_COUNTS: [usize; 65] = include!(concat!(env!("OUT_DIR"), "/window_counts.rs"));
+
+/// Returns the code word of `n`, which must be at least
+/// `CODE_WORDS.len()`, with its first bit in the least significant
+/// bit, along with its length.
+#[inline(always)]
+fn code_word_in_register(n: u64) -> (u128, u32) {
+    let mut digits = 0_u128;
+    let mut remainder = n;
+    let count = WINDOW_COUNTS[64 - n.leading_zeros() as usize];
+    for window in WINDOWS[..count].iter().rev() {
+        let bucket = &window.buckets[(remainder >> window.shift) as usize];
+        let (sum, window_digits) = if remainder >= bucket.high {
+            (bucket.high, bucket.high_digits)
+        } else {
+            (bucket.low, bucket.low_digits)
+        };
+        remainder -= sum;
+        digits |= (window_digits as u128) << window.start;
+    }
+    if remainder > 0 {
+        // The remainder's code word, without its terminator:
+        let low = CODE_WORDS[remainder as usize];
+        digits |= (low & ((1 << ((low >> 24) - 1)) - 1)) as u128;
+    }
+    let split_pos = 127 - digits.leading_zeros();
+    (digits | 1 << (split_pos + 1), split_pos + 2)
+}
+
+/// Fibonacci-encodes the number `n + 1`, without having to represent
+/// it in `T`. This allows encoding `T::MAX + 1`, which is what makes
+/// zero-inclusive encodings possible.
+#[inline]
+pub(crate) fn bits_from_table_plus_one<T, S>(
+    n: T,
+    table: &[T],
+    result: &mut S,
+) -> Result<(), EncodeError<T>>
+where
+    T: FibonacciInteger,
+    S: BitSink,
+{
+    bits_from_table_plus(n, T::one(), table, result)
+}
+
+/// Fibonacci-encodes the number `n + offset`, without having to
+/// represent it in `T`.
+#[inline]
+pub(crate) fn bits_from_table_plus<T, S>(
+    n: T,
+    offset: T,
+    table: &[T],
+    result: &mut S,
+) -> Result<(), EncodeError<T>>
+where
+    T: FibonacciInteger,
+    S: BitSink,
+{
+    if let (Some(word), Some(offset)) = (n.word_value(), offset.word_value()) {
+        if code_word_from_word(word, offset, result) {
+            return Ok(());
+        }
+    }
+    // Elements smaller than the offset are always <= n + offset;
+    // otherwise, an element is <= n + offset if it is <= n after
+    // subtracting the offset:
     let split_pos = table
         .iter()
-        .rposition(|elt| *elt <= n)
+        .rposition(|elt| elt.checked_sub(&offset).is_none_or(|elt| elt <= n))
         .ok_or(EncodeError::ValueTooSmall::<T>(n))?;
+    let elt = table[split_pos];
+    let remainder = match elt.checked_sub(&offset) {
+        Some(elt) => n.checked_sub(&elt),
+        None => n.checked_add(&(offset - elt)),
+    }
+    .ok_or(EncodeError::Underflow(n))?;
+    code_word_from_table(n, split_pos, remainder, table, result)
+}
 
-    let mut i = result.len() + split_pos + 1;
-    result.grow(split_pos + 2, false);
-    result.set(i, true);
-    for elt in table.split_at(split_pos + 1).0.iter().rev() {
+/// Appends the terminator that ends a stream of code words encoded
+/// with [`Encode::fib_encode_terminated`]: the code word "11".
+#[inline]
+pub(crate) fn push_stream_terminator<S: BitSink>(result: &mut S) {
+    let start = result.len();
+    result.push_zeros(2);
+    result.set_bit(start);
+    result.set_bit(start + 1);
+}
+
+/// Appends the code word whose highest set bit is at `split_pos`,
+/// and whose lower bits represent `remainder`.
+#[inline]
+fn code_word_from_table<T, S>(
+    n: T,
+    split_pos: usize,
+    remainder: T,
+    table: &[T],
+    result: &mut S,
+) -> Result<(), EncodeError<T>>
+where
+    T: CheckedSub + PartialOrd + Debug + Clone + Send + Sync + 'static,
+    S: BitSink,
+{
+    let mut current = remainder;
+    let start = result.len();
+    let mut i = start + split_pos;
+    result.push_zeros(split_pos + 2);
+    result.set_bit(i + 1);
+    result.set_bit(i);
+    for elt in table.split_at(split_pos).0.iter().rev() {
         i -= 1;
         if elt <= &current {
             let next = match current.checked_sub(elt) {
@@ -151,12 +669,68 @@ where
                     // I have no idea how it could even occur in real
                     // life. However, let's clean up and return a
                     // reasonable error:
-                    result.truncate(split_pos + 2);
+                    result.truncate(start);
                     return Err(EncodeError::Underflow(n));
                 }
             };
             current = next;
-            result.set(i, true);
+            result.set_bit(i);
+        };
+    }
+    Ok(())
+}
+
+/// Negafibonacci-encodes the number with the given sign and
+/// magnitude. The table holds the magnitudes of the negafibonacci
+/// numbers, whose signs alternate starting with a positive one.
+#[inline]
+pub(crate) fn negafib_bits_from_table<T, S>(
+    negative: bool,
+    magnitude: T,
+    table: &'static [T],
+    result: &mut S,
+) -> Result<(), EncodeError<T>>
+where
+    T: CheckedSub + Zero + PartialOrd + Debug + Copy + Send + Sync + 'static,
+    S: BitSink,
+{
+    if magnitude.is_zero() {
+        return Err(EncodeError::ValueTooSmall(magnitude));
+    }
+    let start = result.len();
+    let mut negative = negative;
+    let mut current = magnitude;
+    while !current.is_zero() {
+        // Positive numbers have their highest digit at an even
+        // position, negative numbers at an odd one. All numbers whose
+        // highest digit is at `pos` have a magnitude no greater than
+        // the element at `pos + 1` (strictly smaller, if negative):
+        let mut pos = if negative { 1 } else { 0 };
+        while let Some(bound) = table.get(pos + 1) {
+            if *bound > current || (!negative && *bound == current) {
+                break;
+            }
+            pos += 2;
+        }
+        let elt = match table.get(pos) {
+            Some(elt) => *elt,
+            None => {
+                // The table is too short for this number, which is a
+                // bug in the generated tables:
+                result.truncate(start);
+                return Err(EncodeError::Underflow(magnitude));
+            }
+        };
+        if result.len() == start {
+            result.push_zeros(pos + 2);
+            result.set_bit(start + pos + 1);
+        }
+        result.set_bit(start + pos);
+        current = if elt <= current {
+            current - elt
+        } else {
+            negative = !negative;
+            elt - current
         };
     }
     Ok(())
@@ -0,0 +1,290 @@
+use crate::bit_order::BitOrder;
+use crate::decode::DecodeError;
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::fmt::Debug;
+use std::iter;
+
+#[cfg(doc)]
+use crate::integer::FibonacciInteger;
+
+/// The number of words that a [`FibDecoder`] hands to
+/// [`PushBits::push_words`] at once.
+const BLOCK_WORDS: usize = if cfg!(all(feature = "simd", target_arch = "x86_64")) {
+    32
+} else {
+    1
+};
+
+/// Integer types that can be decoded one bit at a time, keeping the
+/// state of a partially decoded code word in between, so that a
+/// [`FibDecoder`] can decode them.
+///
+/// This crate implements this trait for all primitive integer types,
+/// the `NonZero*` unsigned integer types, all [`FibonacciInteger`]
+/// types and, with the `bigint` feature, `BigUint`. The trait is
+/// sealed: no other types can implement it.
+pub trait IncrementalDecode: PushBits {}
+
+pub(crate) use private::PushBits;
+
+mod private {
+    use crate::decode::DecodeError;
+    use std::fmt::Debug;
+
+    /// How an [`IncrementalDecode`](super::IncrementalDecode) type decodes
+    /// bits. Public, but in a private module, so that other crates can
+    /// neither name nor implement it.
+    pub trait PushBits: Sized {
+        /// The state of decoding a code word.
+        type State: Debug;
+
+        /// Returns the state at the start of a code word.
+        fn new_state() -> Self::State;
+
+        /// Returns the state at the start of a code word in a stream
+        /// encoded with
+        /// [`fib_encode_terminated`](crate::Encode::fib_encode_terminated),
+        /// in which each number's code word is that of the next larger
+        /// number. Callers need to check for the terminator before, and
+        /// not push its bits.
+        fn new_terminated_state() -> Self::State;
+
+        /// Decodes the next bit of a code word. Returns the result once
+        /// the code word is complete, and resets `state` for the next one.
+        fn push_bit(state: &mut Self::State, bit: bool) -> Option<Result<Self, DecodeError>>;
+
+        /// Decodes the next eight bits, whose first one is the least
+        /// significant bit of `bits`, and passes the result of each code
+        /// word that ends in them to `emit`.
+        ///
+        /// The default implementation calls
+        /// [`push_bit`](PushBits::push_bit) for each bit; the
+        /// implementations for integers decode a byte at a time instead.
+        fn push_byte<E>(state: &mut Self::State, bits: u8, mut emit: E)
+        where
+            E: FnMut(Result<Self, DecodeError>),
+        {
+            for i in 0..8 {
+                if let Some(result) = Self::push_bit(state, bits >> i & 1 == 1) {
+                    emit(result);
+                }
+            }
+        }
+
+        /// Decodes the next 64 bits, whose first one is the least
+        /// significant bit of `bits`, and passes the result of each code
+        /// word that ends in them to `emit`.
+        ///
+        /// The default implementation calls
+        /// [`push_byte`](PushBits::push_byte) for each byte; the
+        /// implementations for integers find the ends of the code words in
+        /// the whole word at once instead.
+        fn push_word<E>(state: &mut Self::State, bits: u64, mut emit: E)
+        where
+            E: FnMut(Result<Self, DecodeError>),
+        {
+            for i in 0..8 {
+                Self::push_byte(state, (bits >> (8 * i)) as u8, &mut emit);
+            }
+        }
+
+        /// Decodes the next words of 64 bits each, like
+        /// [`push_word`](PushBits::push_word) does.
+        ///
+        /// The default implementation calls `push_word` for each word; with
+        /// the `simd` feature, the implementations for integers look at
+        /// several words at once.
+        fn push_words<E>(state: &mut Self::State, words: &[u64], mut emit: E)
+        where
+            E: FnMut(Result<Self, DecodeError>),
+        {
+            for bits in words {
+                Self::push_word(state, *bits, &mut emit);
+            }
+        }
+
+        /// Ends the input after the bits pushed so far, and resets
+        /// `state`. Returns an error if they don't form a complete code
+        /// word and aren't just padding.
+        fn finish_bits(state: &mut Self::State) -> Result<(), DecodeError>;
+    }
+}
+
+/// A push-based decoder that decodes numbers of type `T` from bytes
+/// that arrive in chunks of any size.
+///
+/// Unlike the decoding iterators, which pull bits from a single source,
+/// a `FibDecoder` is handed each chunk via [`feed`](FibDecoder::feed),
+/// and carries a code word that continues in the next chunk over to
+/// it. Once all chunks are fed, [`finish`](FibDecoder::finish)
+/// reports a code word that was left dangling.
+///
+/// It decodes 64 bits at a time: the ends of the code words in them
+/// are the set bits that follow another set bit, which a few bit
+/// operations find, so that only the bits that are set need to be
+/// looked at. With the `simd` feature, it finds them in many words at
+/// once with AVX2 or SSE2 instructions, if the CPU supports them. The
+/// bytes at the end of a chunk that don't fill a word are decoded a
+/// byte at a time, looking up where code words end in a precomputed
+/// table.
+/// The results, including errors, are the same as those of
+/// [`fib_decode_bytes`](crate::fib_decode_bytes).
+///
+/// Buffers of `u64` words can be decoded via
+/// [`feed_words`](FibDecoder::feed_words).
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{Encode, FibDecoder};
+///
+/// let bytes = vec![1_u32, 50, 3003].fib_encode().unwrap().to_bytes();
+/// let mut decoder = FibDecoder::<u32>::new();
+/// let mut decoded = vec![];
+/// for chunk in bytes.chunks(3) {
+///     decoded.extend(decoder.feed(chunk).map(|n| n.unwrap()));
+/// }
+/// decoder.finish().unwrap();
+/// assert_eq!(vec![1, 50, 3003], decoded);
+/// ```
+#[derive(Debug)]
+pub struct FibDecoder<T: IncrementalDecode> {
+    state: T::State,
+    order: BitOrder,
+    pending: VecDeque<Result<T, DecodeError>>,
+}
+
+impl<T: IncrementalDecode> FibDecoder<T> {
+    /// Returns a decoder that reads the bits of each byte most
+    /// significant bit first.
+    pub fn new() -> FibDecoder<T> {
+        FibDecoder::with_bit_order(BitOrder::MsbFirst)
+    }
+
+    /// Returns a decoder that reads the bits of each byte in the
+    /// given order.
+    pub fn with_bit_order(order: BitOrder) -> FibDecoder<T> {
+        FibDecoder {
+            state: T::new_state(),
+            order,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Decodes the next chunk of bytes, returning an iterator over the
+    /// numbers whose code words end in it.
+    ///
+    /// The whole chunk is decoded before this returns. Results that the
+    /// iterator doesn't get to hand out are kept, and handed out first
+    /// by the iterator that the next call returns; feeding an empty
+    /// chunk hands out just those.
+    pub fn feed(&mut self, bytes: &[u8]) -> impl Iterator<Item = Result<T, DecodeError>> + '_ {
+        self.push_bytes(bytes);
+        self.drain()
+    }
+
+    /// Decodes a chunk of bytes, keeping the results for
+    /// [`drain`](FibDecoder::drain) to hand out.
+    pub(crate) fn push_bytes(&mut self, bytes: &[u8]) {
+        let mut blocks = bytes.chunks_exact(8 * BLOCK_WORDS);
+        for block in &mut blocks {
+            self.push_block(block);
+        }
+        let rest = blocks.remainder();
+        let (block, rest) = rest.split_at(rest.len() / 8 * 8);
+        self.push_block(block);
+        let pending = &mut self.pending;
+        for byte in rest {
+            T::push_byte(&mut self.state, self.order.lsb_first(*byte), |result| {
+                pending.push_back(result)
+            });
+        }
+    }
+
+    /// Decodes up to `BLOCK_WORDS` words of eight bytes each.
+    fn push_block(&mut self, block: &[u8]) {
+        let mut words = [0; BLOCK_WORDS];
+        let n = block.len() / 8;
+        for (word, bytes) in words.iter_mut().zip(block.chunks_exact(8)) {
+            let bits = self
+                .order
+                .word_from_bytes(bytes.try_into().expect("8 bytes"));
+            *word = self.order.lsb_first_word(bits);
+        }
+        let pending = &mut self.pending;
+        T::push_words(&mut self.state, &words[..n], |result| {
+            pending.push_back(result)
+        });
+    }
+
+    /// Decodes the next chunk of `u64` words, returning an iterator
+    /// over the numbers whose code words end in it, like
+    /// [`feed`](FibDecoder::feed) does for bytes.
+    ///
+    /// The decoder's bit order applies to the bits of each word: by
+    /// default, the most significant bit is read first. Either way,
+    /// feeding a word gives the same results as feeding the bytes that
+    /// [`u64::to_be_bytes`] (or, if the least significant bit is read
+    /// first, [`u64::to_le_bytes`]) returns for it.
+    /// In particular, more than seven zero bits after the last code
+    /// word don't count as padding, so a stream that doesn't fill its
+    /// last word is better fed as bytes.
+    ///
+    /// # Example
+    /// ``` rust
+    /// use fibonacci_codec::{BitOrder, Encode, FibDecoder};
+    ///
+    /// // 64 bits, without any padding:
+    /// let bits = vec![1_u32, 50, 3003, 10_000_000].fib_encode().unwrap();
+    /// assert_eq!(64, bits.len());
+    /// let mut words = vec![0_u64; bits.len().div_ceil(64)];
+    /// for (i, bit) in bits.iter().enumerate() {
+    ///     words[i / 64] |= (bit as u64) << (i % 64);
+    /// }
+    ///
+    /// let mut decoder = FibDecoder::<u32>::with_bit_order(BitOrder::LsbFirst);
+    /// let decoded: Vec<u32> = decoder.feed_words(&words).map(|n| n.unwrap()).collect();
+    /// decoder.finish().unwrap();
+    /// assert_eq!(vec![1, 50, 3003, 10_000_000], decoded);
+    /// ```
+    pub fn feed_words(
+        &mut self,
+        words: &[u64],
+    ) -> impl Iterator<Item = Result<T, DecodeError>> + '_ {
+        let pending = &mut self.pending;
+        for block in words.chunks(BLOCK_WORDS) {
+            let mut bits = [0; BLOCK_WORDS];
+            for (bits, word) in bits.iter_mut().zip(block) {
+                *bits = self.order.lsb_first_word(*word);
+            }
+            T::push_words(&mut self.state, &bits[..block.len()], |result| {
+                pending.push_back(result)
+            });
+        }
+        self.drain()
+    }
+
+    /// Hands out the results decoded so far.
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = Result<T, DecodeError>> + '_ {
+        iter::from_fn(move || self.pending.pop_front())
+    }
+
+    /// Ends the input. Returns an error if the chunks fed so far end in
+    /// the middle of a code word: [`DecodeError::Truncated`] for a
+    /// dangling partial code word, or the error of a code word that
+    /// already failed to decode. Zero bits that pad the last byte are
+    /// not an error.
+    ///
+    /// Afterwards, the decoder is ready to decode a new stream. Results
+    /// that weren't handed out yet are kept for the next call to
+    /// [`feed`](FibDecoder::feed).
+    pub fn finish(&mut self) -> Result<(), DecodeError> {
+        T::finish_bits(&mut self.state)
+    }
+}
+
+impl<T: IncrementalDecode> Default for FibDecoder<T> {
+    fn default() -> FibDecoder<T> {
+        FibDecoder::new()
+    }
+}
@@ -0,0 +1,275 @@
+#[cfg(doc)]
+use crate::decode::fib_decode;
+use crate::decode::{decode_from, decode_from_minus_one, Decode, DecodeError, WordState};
+use crate::encode::{
+    bits_from_table_plus_one, bits_from_word_or_table, push_stream_terminator, ElementEncodeError,
+    Encode, EncodeElement, EncodeError, EncodeOne, EncodeOneWithZero, EncodeWithZero,
+};
+use crate::incremental::{IncrementalDecode, PushBits};
+use crate::sink::BitSink;
+use crate::source::BitSource;
+use num::{CheckedAdd, CheckedSub, One, Zero};
+use std::borrow::Borrow;
+use std::fmt::Debug;
+use std::iter::successors;
+
+/// An unsigned integer type that can be fibonacci-coded.
+///
+/// This crate implements the trait for the primitive unsigned integer
+/// types. Implementing it for other types (e.g. newtypes wrapping a
+/// primitive integer, or integers of unusual width) gives them
+/// [`EncodeOne`], [`Encode`], the zero-inclusive encoding traits and
+/// [`Decode`], so they can be decoded via [`fib_decode`].
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{fib_decode, fibonacci_table, Encode, FibonacciInteger};
+/// use num::{CheckedAdd, CheckedSub, One, Zero};
+/// use std::ops::{Add, Mul, Sub};
+/// use std::sync::OnceLock;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+/// struct UserId(u32);
+///
+/// impl Add for UserId {
+///     type Output = Self;
+///     fn add(self, other: Self) -> Self { UserId(self.0 + other.0) }
+/// }
+/// impl Sub for UserId {
+///     type Output = Self;
+///     fn sub(self, other: Self) -> Self { UserId(self.0 - other.0) }
+/// }
+/// impl Mul for UserId {
+///     type Output = Self;
+///     fn mul(self, other: Self) -> Self { UserId(self.0 * other.0) }
+/// }
+/// impl CheckedAdd for UserId {
+///     fn checked_add(&self, other: &Self) -> Option<Self> {
+///         self.0.checked_add(other.0).map(UserId)
+///     }
+/// }
+/// impl CheckedSub for UserId {
+///     fn checked_sub(&self, other: &Self) -> Option<Self> {
+///         self.0.checked_sub(other.0).map(UserId)
+///     }
+/// }
+/// impl Zero for UserId {
+///     fn zero() -> Self { UserId(0) }
+///     fn is_zero(&self) -> bool { self.0 == 0 }
+/// }
+/// impl One for UserId {
+///     fn one() -> Self { UserId(1) }
+/// }
+///
+/// impl FibonacciInteger for UserId {
+///     fn fibonacci_table() -> &'static [Self] {
+///         static TABLE: OnceLock<Vec<UserId>> = OnceLock::new();
+///         TABLE.get_or_init(fibonacci_table)
+///     }
+/// }
+///
+/// let ids = vec![UserId(1), UserId(3003), UserId(u32::MAX)];
+/// let encoded = ids.clone().fib_encode().unwrap();
+/// let decoded: Vec<UserId> = fib_decode(&encoded).map(|id| id.unwrap()).collect();
+/// assert_eq!(ids, decoded);
+/// ```
+pub trait FibonacciInteger:
+    CheckedAdd + CheckedSub + Zero + One + PartialOrd + Debug + Copy + Send + Sync + 'static
+{
+    /// Returns the fibonacci numbers 1, 2, 3, 5, 8, ... in ascending
+    /// order, up to the largest one that can be represented in `Self`.
+    ///
+    /// See [`fibonacci_table`] for a way to build this table.
+    fn fibonacci_table() -> &'static [Self];
+
+    /// Returns the number as a `u64`, or `None` if it doesn't fit in
+    /// one.
+    ///
+    /// The code words of numbers that fit are built in a register
+    /// (those of numbers up to `u8::MAX` are looked up in a table that
+    /// the build script precomputes) and appended all at once, rather
+    /// than computed bit by bit from
+    /// [`fibonacci_table`](FibonacciInteger::fibonacci_table). The
+    /// default implementation returns `None`, so they're always
+    /// computed from the table.
+    fn word_value(self) -> Option<u64> {
+        None
+    }
+}
+
+/// Computes the fibonacci numbers 1, 2, 3, 5, 8, ... until the next
+/// one would overflow `T`, for use as a
+/// [`FibonacciInteger::fibonacci_table`].
+pub fn fibonacci_table<T>() -> Vec<T>
+where
+    T: CheckedAdd + One + Copy,
+{
+    successors(Some((T::one(), T::one())), |(prev, cur)| {
+        prev.checked_add(cur).map(|next| (*cur, next))
+    })
+    .map(|(_, cur)| cur)
+    .collect()
+}
+
+impl<T> EncodeOne for T
+where
+    T: FibonacciInteger,
+{
+    type Error = EncodeError<T>;
+
+    fn fib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), EncodeError<T>> {
+        bits_from_word_or_table(self, T::fibonacci_table(), sink)
+    }
+}
+
+impl<T, I> Encode<T> for I
+where
+    T: FibonacciInteger,
+    I: IntoIterator + Debug + Send + Sync,
+    I::Item: EncodeElement<Integer = T>,
+{
+    type Error = ElementEncodeError<T>;
+
+    fn fib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), ElementEncodeError<T>> {
+        let table = T::fibonacci_table();
+        for (i, elt) in self.into_iter().enumerate() {
+            match bits_from_word_or_table(*elt.borrow(), table, sink) {
+                Ok(_) => {}
+                Err(e) => {
+                    return Err(ElementEncodeError { index: i, error: e });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fib_encode_terminated_into<S: BitSink>(
+        self,
+        sink: &mut S,
+    ) -> Result<(), ElementEncodeError<T>> {
+        let table = T::fibonacci_table();
+        for (i, elt) in self.into_iter().enumerate() {
+            let n = *elt.borrow();
+            // 0 would be shifted to 1, whose code word is the
+            // terminator:
+            let result = if n.is_zero() {
+                Err(EncodeError::ValueTooSmall(n))
+            } else {
+                bits_from_table_plus_one(n, table, sink)
+            };
+            if let Err(e) = result {
+                return Err(ElementEncodeError { index: i, error: e });
+            }
+        }
+        push_stream_terminator(sink);
+        Ok(())
+    }
+}
+
+impl<T> EncodeElement for T
+where
+    T: FibonacciInteger,
+{
+    type Integer = T;
+}
+
+impl<T> EncodeOneWithZero for T
+where
+    T: FibonacciInteger,
+{
+    fn fib_encode_with_zero_into<S: BitSink>(self, sink: &mut S) -> Result<(), EncodeError<T>> {
+        bits_from_table_plus_one(self, T::fibonacci_table(), sink)
+    }
+}
+
+impl<T, I> EncodeWithZero<T> for I
+where
+    T: FibonacciInteger,
+    I: IntoIterator + Debug + Send + Sync,
+    I::Item: EncodeElement<Integer = T>,
+{
+    fn fib_encode_with_zero_into<S: BitSink>(
+        self,
+        sink: &mut S,
+    ) -> Result<(), ElementEncodeError<T>> {
+        let table = T::fibonacci_table();
+        for (i, elt) in self.into_iter().enumerate() {
+            match bits_from_table_plus_one(*elt.borrow(), table, sink) {
+                Ok(_) => {}
+                Err(e) => {
+                    return Err(ElementEncodeError { index: i, error: e });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> Decode for T
+where
+    T: FibonacciInteger,
+{
+    fn fib_decode_next<S>(bits: &mut S) -> Option<Result<T, DecodeError>>
+    where
+        S: BitSource + ?Sized,
+    {
+        decode_from(bits, T::fibonacci_table())
+    }
+
+    fn fib_decode_next_terminated<S>(bits: &mut S) -> Option<Result<T, DecodeError>>
+    where
+        S: BitSource + ?Sized,
+    {
+        decode_from_minus_one(bits, T::fibonacci_table())
+    }
+}
+
+impl<T> IncrementalDecode for T where T: FibonacciInteger {}
+
+impl<T> PushBits for T
+where
+    T: FibonacciInteger,
+{
+    type State = WordState<T>;
+
+    fn new_state() -> WordState<T> {
+        WordState::new(T::zero())
+    }
+
+    fn new_terminated_state() -> WordState<T> {
+        WordState::new(T::one())
+    }
+
+    #[inline]
+    fn push_bit(state: &mut WordState<T>, bit: bool) -> Option<Result<T, DecodeError>> {
+        state.push(bit, &mut T::fibonacci_table())
+    }
+
+    #[inline]
+    fn push_byte<E>(state: &mut WordState<T>, bits: u8, emit: E)
+    where
+        E: FnMut(Result<T, DecodeError>),
+    {
+        state.push_byte(bits, T::fibonacci_table(), emit)
+    }
+
+    #[inline]
+    fn push_word<E>(state: &mut WordState<T>, bits: u64, emit: E)
+    where
+        E: FnMut(Result<T, DecodeError>),
+    {
+        state.push_word(bits, T::fibonacci_table(), emit)
+    }
+
+    #[inline]
+    fn push_words<E>(state: &mut WordState<T>, words: &[u64], emit: E)
+    where
+        E: FnMut(Result<T, DecodeError>),
+    {
+        state.push_words(words, T::fibonacci_table(), emit)
+    }
+
+    fn finish_bits(state: &mut WordState<T>) -> Result<(), DecodeError> {
+        state.finish_stream()
+    }
+}
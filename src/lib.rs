@@ -22,11 +22,107 @@
 //! than 1, up to the range of the given machine integer type. This
 //! means that the integer zero can not be encoded.
 //!
-//! If you should need to encode `0`, it is advisable to encode
-//! numbers incremented by one (preventing overflow by upgrading to
-//! the next-biggest integer type, or by not encoding the maximum
-//! value), and to subtract one from the decoded result.
-//! .
+//! If you should need to encode `0`, use the zero-inclusive
+//! [`EncodeOneWithZero`] and [`EncodeWithZero`] traits, and decode
+//! with `fib_decode_u8_with_zero` through `fib_decode_u128_with_zero`.
+//! These encode each number incremented by one, and subtract one from
+//! the decoded result. They do so without ever representing `n + 1`
+//! in the integer type, so the maximum value of each type can be
+//! encoded as well.
+//!
+//! The `NonZeroU8` through `NonZeroU128` types can always be
+//! encoded, so their [`EncodeOne`] and [`Encode`] implementations use
+//! [`Infallible`](std::convert::Infallible) as their error type.
+//! Decode them with `fib_decode_nonzero_u8` through
+//! `fib_decode_nonzero_u128`.
+//!
+//! With the `bigint` feature enabled, arbitrary-precision
+//! [`num::BigUint`] integers can be encoded too, and decoded with
+//! `fib_decode_biguint`.
+//!
+//! Other unsigned integer types, like newtypes wrapping a primitive
+//! integer, can use the codec by implementing [`FibonacciInteger`].
+//!
+//! ## Decoding generically
+//!
+//! Besides the per-type functions like `fib_decode_u32`, the generic
+//! [`fib_decode`] function decodes into any type implementing the
+//! [`Decode`] trait: all primitive integer types, the `NonZero*`
+//! unsigned types and all [`FibonacciInteger`] types. All of these
+//! return the same [`DecodeIter`] type, so code that decodes integers
+//! of any width can be written once:
+//!
+//! ``` rust
+//! use fibonacci_codec::{fib_decode, Encode};
+//!
+//! let encoded = vec![-3_i64, 0, 70].fib_encode().unwrap();
+//! let decoded: Vec<i64> = fib_decode::<i64, _>(&encoded).map(|n| n.unwrap()).collect();
+//! assert_eq!(vec![-3, 0, 70], decoded);
+//! ```
+//!
+//! ## Decoding bytes
+//!
+//! Encoded bits are usually stored as bytes, e.g. via
+//! `BitVec::to_bytes`. [`fib_decode_bytes`] decodes such bytes (a
+//! `&[u8]` or `Vec<u8>`) directly, without building a `BitVec` first.
+//! To use one of the other decoding functions on bytes, pass them a
+//! [`ByteBits`] iterator.
+//!
+//! To encode more numbers than fit in memory, [`FibWriter`] encodes
+//! them one at a time into any [`std::io::Write`]r, producing the same
+//! bytes. [`FibReader`] decodes numbers from any [`std::io::Read`]er
+//! in constant memory. When the bytes arrive in chunks that you are
+//! handed, rather than read, feed them to a [`FibDecoder`], which
+//! carries code words that are split across chunks over to the next
+//! one. It is also the fastest way to decode primitive integers from
+//! bytes, or `u64` words, that are in memory already; with the `simd`
+//! feature enabled, it uses AVX2 or SSE2 instructions on x86_64 CPUs
+//! that support them.
+//!
+//! With the `tokio` feature enabled, `AsyncFibWriter` and
+//! `AsyncFibReader` do the same for tokio's `AsyncWrite` and
+//! `AsyncRead`, and `FibCodec` sends batches of numbers as frames via
+//! `tokio_util`'s `FramedWrite` and `FramedRead`.
+//!
+//! All of these store the bits of each byte most significant bit
+//! first, like `BitVec::to_bytes`. To read or write bytes whose least
+//! significant bit comes first, pass [`BitOrder::LsbFirst`] to their
+//! `*_with_order` and `with_bit_order` variants.
+//!
+//! With the `bitvec` feature enabled, numbers can be encoded onto the
+//! end of the [`bitvec`](https://docs.rs/bitvec) crate's `BitVec<T, O>`
+//! via the `*_into` methods, and decoded from its `&BitSlice<T, O>`
+//! with `fib_decode_bitslice`, without converting to or from this
+//! crate's bit vectors.
+//!
+//! Other buffers, e.g. ring buffers or memory-mapped regions, can be
+//! encoded into by implementing [`BitSink`] for them, and decoded from
+//! by implementing [`BitSource`] and calling [`fib_decode_source`].
+//!
+//! Since padding bits are zero, they can't always be told apart from
+//! the beginning of a number that got cut off. Streams encoded with
+//! [`Encode::fib_encode_terminated`] end in a terminator code word
+//! instead, and [`fib_decode_terminated`] stops decoding right after
+//! it.
+//!
+//! ## Signed integers
+//!
+//! Signed integers (`i8` through `i128`) are zigzag-mapped onto the
+//! unsigned integer type of the same width (`0` to `0`, `-1` to `1`,
+//! `1` to `2`, `-2` to `3` and so on), and that number plus one gets
+//! encoded. This means that every signed integer, including zero and
+//! the type's minimum and maximum value, can be encoded, and that
+//! numbers close to zero take up the fewest bits.
+//!
+//! ## Negafibonacci coding
+//!
+//! As an alternative to the zigzag mapping, signed integers can be
+//! encoded using [negafibonacci
+//! coding](https://en.wikipedia.org/wiki/Negafibonacci_coding) via
+//! the [`NegafibEncodeOne`] and [`NegafibEncode`] traits, and
+//! decoded with `negafib_decode_i8` through `negafib_decode_i128`.
+//! Negafibonacci coding represents every signed integer except zero
+//! directly, and its code words end in "11" as well.
 //!
 //! # Examples
 //!
@@ -43,32 +139,97 @@
 //!
 //! ## Encoding the value zero:
 //! ``` rust
-//! use fibonacci_codec::Encode;
+//! use fibonacci_codec::{fib_decode_u16_with_zero, EncodeWithZero};
 //!
 //! let numbers: Vec<u16> = vec![0, 49, 3002];
-//! let adjusted: Vec<u32> = numbers.iter().map(|n| *n as u32 + 1).collect();
-//! let encoded = &adjusted.fib_encode().unwrap();
-//! // code words: "11" (1), "001001011" (50), "000010010000100011" (3003)
+//! let encoded = numbers.clone().fib_encode_with_zero().unwrap();
+//! // code words: "11" (0), "001001011" (49), "000010010000100011" (3002)
 //! // These encoded words take up 4 bytes instead of 6 (3*16 bits)!
 //! assert_eq!(encoded.to_bytes(), [0b11001001, 0b01100001, 0b00100001, 0b00011000]);
+//! let decoded: Vec<u16> = fib_decode_u16_with_zero(encoded).map(|n| n.unwrap()).collect();
+//! assert_eq!(numbers, decoded);
+//! ```
+//!
+//! ## Encoding signed numbers:
+//! ``` rust
+//! use fibonacci_codec::{fib_decode_i32, Encode};
+//!
+//! let numbers: Vec<i32> = vec![0, -1, 1, i32::MIN];
+//! let encoded = numbers.clone().fib_encode().unwrap();
+//! let decoded: Vec<i32> = fib_decode_i32(encoded).map(|n| n.unwrap()).collect();
+//! assert_eq!(numbers, decoded);
+//! ```
+//!
+//! ## Negafibonacci-encoding signed numbers:
+//! ``` rust
+//! use fibonacci_codec::{negafib_decode_i16, NegafibEncode};
+//!
+//! let numbers: Vec<i16> = vec![1, -1, 2, -3];
+//! let encoded = numbers.clone().negafib_encode().unwrap();
+//! // code words: "11" (1), "011" (-1), "0011" (2), "00011" (-3)
+//! assert_eq!(encoded.to_bytes(), [0b11011001, 0b10001100]);
+//! let decoded: Vec<i16> = negafib_decode_i16(encoded).map(|n| n.unwrap()).collect();
+//! assert_eq!(numbers, decoded);
 //! ```
 //!
 //! # References:
 //! * [Wikipedia](https://en.wikipedia.org/wiki/Fibonacci_coding)
 //! * [Fraenkel, Aviezri S.; Klein, Shmuel T. (1996). "Robust universal complete codes for transmission and compression"](http://citeseerx.ist.psu.edu/viewdoc/summary?doi=10.1.1.37.3064)
 
+// failure_derive's generated impls trip this lint on newer compilers:
+#![allow(non_local_definitions)]
+
 #[macro_use]
 extern crate failure_derive;
 
 // Macros need to be defined first:
 mod macros;
 
+#[cfg(feature = "tokio")]
+mod async_io;
+#[cfg(feature = "bigint")]
+pub mod biguint;
+mod bit_order;
+#[cfg(feature = "bitvec")]
+mod bit_slice;
+mod byte_slice;
+#[cfg(feature = "tokio")]
+mod codec;
 mod decode;
 mod encode;
+mod incremental;
+mod integer;
+mod reader;
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod simd;
+mod sink;
+mod source;
+mod table;
+mod writer;
 pub mod int {
     include!(concat!(env!("OUT_DIR"), "/int.rs"));
 }
 
-pub use crate::decode::DecodeError;
+pub use crate::bit_order::BitOrder;
+pub use crate::byte_slice::{fib_decode_bytes, fib_decode_bytes_with_order, ByteBits};
+pub use crate::decode::{
+    fib_decode, fib_decode_source, fib_decode_terminated, Decode, DecodeError, DecodeIter,
+    TerminatedDecodeIter,
+};
 pub use crate::encode::*;
+pub use crate::incremental::{FibDecoder, IncrementalDecode};
 pub use crate::int::funcs::*;
+pub use crate::integer::{fibonacci_table, FibonacciInteger};
+pub use crate::reader::FibReader;
+pub use crate::sink::{BitSink, ByteSink};
+pub use crate::source::{BitSource, WordBits};
+pub use crate::writer::FibWriter;
+
+#[cfg(feature = "tokio")]
+pub use crate::async_io::{AsyncFibReader, AsyncFibWriter};
+#[cfg(feature = "bigint")]
+pub use crate::biguint::fib_decode_biguint;
+#[cfg(feature = "bitvec")]
+pub use crate::bit_slice::fib_decode_bitslice;
+#[cfg(feature = "tokio")]
+pub use crate::codec::FibCodec;
@@ -6,37 +6,294 @@ macro_rules! impl_fib_encode_for_integral_type {
         $typename_str:expr,
         $decoder_name:ident,
         $decode_name:ident,
+        $zero_decoder_name:ident,
+        $nonzero:ident,
+        $nonzero_decoder_name:ident,
         $table:expr
     ) => {
         #[doc = "Functions and iterators to decode `"]
         #[doc = $typename_str]
         #[doc = "` integers."]
         pub mod $typename {
-            use crate::decode::{decode_from, DecodeError};
+            use crate::decode::{
+                decode_from, decode_from_minus_one, Decode, DecodeError, WordState,
+            };
             use crate::encode::{
-                bits_from_table, ElementEncodeError, Encode, EncodeError, EncodeOne,
+                bits_from_table_plus_one, bits_from_word_or_table, push_stream_terminator, Encode,
+                EncodeElement, EncodeOne,
             };
-            use bit_vec::BitVec;
+            use crate::incremental::{IncrementalDecode, PushBits};
+            use crate::integer::FibonacciInteger;
+            use crate::sink::BitSink;
+            use crate::source::BitSource;
+            use std::borrow::Borrow;
+            use std::convert::{Infallible, TryFrom};
             use std::fmt::Debug;
+            use std::num::$nonzero;
 
             pub(crate) const TABLE: &'static [$typename] = &($table);
 
+            impl FibonacciInteger for $typename {
+                fn fibonacci_table() -> &'static [$typename] {
+                    TABLE
+                }
+
+                #[inline]
+                fn word_value(self) -> Option<u64> {
+                    u64::try_from(self).ok()
+                }
+            }
+
+            #[doc = "An iterator that yields fibonacci-decoded `"]
+            #[doc = $typename_str ]
+            #[doc = "` integers."]
+            pub type $decoder_name<I> = crate::decode::DecodeIter<I, $typename>;
+
+            #[doc = "An iterator that yields fibonacci-decoded `"]
+            #[doc = $typename_str ]
+            #[doc = "` integers, each offset by one so that zero can be represented."]
+            pub struct $zero_decoder_name<I> {
+                pub(crate) orig: I,
+            }
+
+            impl<I: BitSource> Iterator for $zero_decoder_name<I> {
+                #[doc = "This iterator yields `Ok("]
+                #[doc = $typename_str]
+                #[doc = ")` when a number could be decoded successfully and returns an error"]
+                #[doc = "otherwise."]
+                type Item = Result<$typename, DecodeError>;
+
+                fn next(&mut self) -> Option<Self::Item> {
+                    decode_from_minus_one(&mut self.orig, TABLE)
+                }
+            }
+
+            impl<'a> EncodeElement for &'a $typename {
+                type Integer = $typename;
+            }
+
+            impl EncodeElement for $nonzero {
+                type Integer = $nonzero;
+            }
+
+            impl<'a> EncodeElement for &'a $nonzero {
+                type Integer = $nonzero;
+            }
+
+            impl EncodeOne for $nonzero {
+                type Error = Infallible;
+
+                fn fib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), Infallible> {
+                    bits_from_word_or_table(self.get(), TABLE, sink)
+                        .expect("non-zero integers can always be encoded");
+                    Ok(())
+                }
+            }
+
+            impl<T> Encode<$nonzero> for T
+            where
+                T: IntoIterator + Debug + Send + Sync,
+                T::Item: EncodeElement<Integer = $nonzero>,
+            {
+                type Error = Infallible;
+
+                fn fib_encode_into<S: BitSink>(self, sink: &mut S) -> Result<(), Infallible> {
+                    for elt in self.into_iter() {
+                        bits_from_word_or_table(elt.borrow().get(), TABLE, sink)
+                            .expect("non-zero integers can always be encoded");
+                    }
+                    Ok(())
+                }
+
+                fn fib_encode_terminated_into<S: BitSink>(
+                    self,
+                    sink: &mut S,
+                ) -> Result<(), Infallible> {
+                    for elt in self.into_iter() {
+                        bits_from_table_plus_one(elt.borrow().get(), TABLE, sink)
+                            .expect("non-zero integers can always be encoded");
+                    }
+                    push_stream_terminator(sink);
+                    Ok(())
+                }
+            }
+
+            impl Decode for $nonzero {
+                fn fib_decode_next<S>(bits: &mut S) -> Option<Result<$nonzero, DecodeError>>
+                where
+                    S: BitSource + ?Sized,
+                {
+                    decode_from(bits, TABLE).map(|res| {
+                        res.map(|n| {
+                            $nonzero::new(n).expect("fibonacci code words never decode to 0")
+                        })
+                    })
+                }
+
+                fn fib_decode_next_terminated<S>(
+                    bits: &mut S,
+                ) -> Option<Result<$nonzero, DecodeError>>
+                where
+                    S: BitSource + ?Sized,
+                {
+                    decode_from_minus_one(bits, TABLE).map(|res| {
+                        // Only the terminator decodes to 0:
+                        res.and_then(|n| {
+                            $nonzero::new(n).ok_or(DecodeError::ConstructionOverflow { bit_pos: 0 })
+                        })
+                    })
+                }
+            }
+
+            impl IncrementalDecode for $nonzero {}
+
+            impl PushBits for $nonzero {
+                type State = WordState<$typename>;
+
+                fn new_state() -> WordState<$typename> {
+                    WordState::new(0)
+                }
+
+                fn new_terminated_state() -> WordState<$typename> {
+                    WordState::new(1)
+                }
+
+                #[inline]
+                fn push_bit(
+                    state: &mut WordState<$typename>,
+                    bit: bool,
+                ) -> Option<Result<$nonzero, DecodeError>> {
+                    state.push(bit, &mut { TABLE }).map(|res| {
+                        res.map(|n| {
+                            $nonzero::new(n).expect("fibonacci code words never decode to 0")
+                        })
+                    })
+                }
+
+                #[inline]
+                fn push_byte<E>(state: &mut WordState<$typename>, bits: u8, mut emit: E)
+                where
+                    E: FnMut(Result<$nonzero, DecodeError>),
+                {
+                    state.push_byte(bits, TABLE, |res| {
+                        emit(res.map(|n| {
+                            $nonzero::new(n).expect("fibonacci code words never decode to 0")
+                        }))
+                    })
+                }
+
+                #[inline]
+                fn push_word<E>(state: &mut WordState<$typename>, bits: u64, mut emit: E)
+                where
+                    E: FnMut(Result<$nonzero, DecodeError>),
+                {
+                    state.push_word(bits, TABLE, |res| {
+                        emit(res.map(|n| {
+                            $nonzero::new(n).expect("fibonacci code words never decode to 0")
+                        }))
+                    })
+                }
+
+                #[inline]
+                fn push_words<E>(state: &mut WordState<$typename>, words: &[u64], mut emit: E)
+                where
+                    E: FnMut(Result<$nonzero, DecodeError>),
+                {
+                    state.push_words(words, TABLE, |res| {
+                        emit(res.map(|n| {
+                            $nonzero::new(n).expect("fibonacci code words never decode to 0")
+                        }))
+                    })
+                }
+
+                fn finish_bits(state: &mut WordState<$typename>) -> Result<(), DecodeError> {
+                    state.finish_stream()
+                }
+            }
+
+            #[doc = "An iterator that yields fibonacci-decoded `"]
+            #[doc = stringify!($nonzero)]
+            #[doc = "` integers."]
+            pub type $nonzero_decoder_name<I> = crate::decode::DecodeIter<I, $nonzero>;
+        }
+    };
+}
+
+macro_rules! impl_fib_encode_for_signed_type {
+    (
+        $typename:ident,
+        $typename_str:expr,
+        $unsigned:ident,
+        $decoder_name:ident,
+        $decode_name:ident,
+        $negafib_decoder_name:ident,
+        $negafib_table:expr
+    ) => {
+        #[doc = "Functions and iterators to decode zigzag-mapped or negafibonacci-coded `"]
+        #[doc = $typename_str]
+        #[doc = "` integers."]
+        pub mod $typename {
+            use super::$unsigned::TABLE;
+            use crate::decode::{
+                decode_from_minus_one, decode_offset_from, negafib_decode_from, Decode,
+                DecodeError, WordState,
+            };
+            use crate::encode::{
+                bits_from_table_plus, bits_from_table_plus_one, negafib_bits_from_table,
+                push_stream_terminator, ElementEncodeError, Encode, EncodeElement, EncodeError,
+                EncodeOne, NegafibEncode, NegafibEncodeOne,
+            };
+            use crate::incremental::{IncrementalDecode, PushBits};
+            use crate::sink::BitSink;
+            use crate::source::BitSource;
+            use std::borrow::Borrow;
+            use std::fmt::Debug;
+
+            /// The magnitudes of the negafibonacci numbers; their signs
+            /// alternate, starting with a positive one.
+            pub(crate) const NEGAFIB_TABLE: &'static [$unsigned] = &($negafib_table);
+
+            fn zigzag(n: $typename) -> $unsigned {
+                ((n << 1) ^ (n >> (<$typename>::BITS - 1))) as $unsigned
+            }
+
+            fn unzigzag(n: $unsigned) -> $typename {
+                ((n >> 1) as $typename) ^ -((n & 1) as $typename)
+            }
+
+            impl EncodeElement for $typename {
+                type Integer = $typename;
+            }
+
+            impl<'a> EncodeElement for &'a $typename {
+                type Integer = $typename;
+            }
+
             impl EncodeOne for $typename {
-                fn fib_encode_mut(self, vec: &mut BitVec) -> Result<(), EncodeError<$typename>> {
-                    bits_from_table(self, TABLE, vec)
+                type Error = EncodeError<$typename>;
+
+                fn fib_encode_into<S: BitSink>(
+                    self,
+                    sink: &mut S,
+                ) -> Result<(), EncodeError<$typename>> {
+                    bits_from_table_plus_one(zigzag(self), TABLE, sink)
+                        .map_err(|_| EncodeError::Underflow(self))
                 }
             }
 
             impl<T> Encode<$typename> for T
             where
-                T: IntoIterator<Item = $typename> + Debug + Send + Sync,
+                T: IntoIterator + Debug + Send + Sync,
+                T::Item: EncodeElement<Integer = $typename>,
             {
-                fn fib_encode_mut(
+                type Error = ElementEncodeError<$typename>;
+
+                fn fib_encode_into<S: BitSink>(
                     self,
-                    vec: &mut BitVec,
+                    sink: &mut S,
                 ) -> Result<(), ElementEncodeError<$typename>> {
                     for (i, elt) in self.into_iter().enumerate() {
-                        match bits_from_table(elt, TABLE, vec) {
+                        match elt.borrow().fib_encode_into(sink) {
                             Ok(_) => {}
                             Err(e) => {
                                 return Err(ElementEncodeError { index: i, error: e });
@@ -45,16 +302,151 @@ macro_rules! impl_fib_encode_for_integral_type {
                     }
                     Ok(())
                 }
+
+                fn fib_encode_terminated_into<S: BitSink>(
+                    self,
+                    sink: &mut S,
+                ) -> Result<(), ElementEncodeError<$typename>> {
+                    for (i, elt) in self.into_iter().enumerate() {
+                        let n = *elt.borrow();
+                        // Zigzag-mapped numbers are usually offset by
+                        // one; shift them by one more, so none of them
+                        // uses the terminator's code word:
+                        if bits_from_table_plus(zigzag(n), 2, TABLE, sink).is_err() {
+                            return Err(ElementEncodeError {
+                                index: i,
+                                error: EncodeError::Underflow(n),
+                            });
+                        }
+                    }
+                    push_stream_terminator(sink);
+                    Ok(())
+                }
             }
 
-            #[doc = "An iterator that yields fibonacci-decoded `"]
+            impl Decode for $typename {
+                fn fib_decode_next<S>(bits: &mut S) -> Option<Result<$typename, DecodeError>>
+                where
+                    S: BitSource + ?Sized,
+                {
+                    decode_from_minus_one(bits, TABLE).map(|res| res.map(unzigzag))
+                }
+
+                fn fib_decode_next_terminated<S>(
+                    bits: &mut S,
+                ) -> Option<Result<$typename, DecodeError>>
+                where
+                    S: BitSource + ?Sized,
+                {
+                    decode_offset_from(bits, TABLE, 2).map(|res| res.map(unzigzag))
+                }
+            }
+
+            impl IncrementalDecode for $typename {}
+
+            impl PushBits for $typename {
+                type State = WordState<$unsigned>;
+
+                fn new_state() -> WordState<$unsigned> {
+                    WordState::new(1)
+                }
+
+                fn new_terminated_state() -> WordState<$unsigned> {
+                    WordState::new(2)
+                }
+
+                #[inline]
+                fn push_bit(
+                    state: &mut WordState<$unsigned>,
+                    bit: bool,
+                ) -> Option<Result<$typename, DecodeError>> {
+                    state.push(bit, &mut { TABLE }).map(|res| res.map(unzigzag))
+                }
+
+                #[inline]
+                fn push_byte<E>(state: &mut WordState<$unsigned>, bits: u8, mut emit: E)
+                where
+                    E: FnMut(Result<$typename, DecodeError>),
+                {
+                    state.push_byte(bits, TABLE, |res| emit(res.map(unzigzag)))
+                }
+
+                #[inline]
+                fn push_word<E>(state: &mut WordState<$unsigned>, bits: u64, mut emit: E)
+                where
+                    E: FnMut(Result<$typename, DecodeError>),
+                {
+                    state.push_word(bits, TABLE, |res| emit(res.map(unzigzag)))
+                }
+
+                #[inline]
+                fn push_words<E>(state: &mut WordState<$unsigned>, words: &[u64], mut emit: E)
+                where
+                    E: FnMut(Result<$typename, DecodeError>),
+                {
+                    state.push_words(words, TABLE, |res| emit(res.map(unzigzag)))
+                }
+
+                fn finish_bits(state: &mut WordState<$unsigned>) -> Result<(), DecodeError> {
+                    state.finish_stream()
+                }
+            }
+
+            #[doc = "An iterator that yields fibonacci-decoded, zigzag-mapped `"]
+            #[doc = $typename_str ]
+            #[doc = "` integers."]
+            pub type $decoder_name<I> = crate::decode::DecodeIter<I, $typename>;
+
+            fn map_negafib_error(
+                n: $typename,
+                error: EncodeError<$unsigned>,
+            ) -> EncodeError<$typename> {
+                match error {
+                    EncodeError::ValueTooSmall(_) => EncodeError::ValueTooSmall(n),
+                    EncodeError::ValueTooLarge(_) => EncodeError::ValueTooLarge(n),
+                    EncodeError::Underflow(_) => EncodeError::Underflow(n),
+                }
+            }
+
+            impl NegafibEncodeOne for $typename {
+                fn negafib_encode_into<S: BitSink>(
+                    self,
+                    sink: &mut S,
+                ) -> Result<(), EncodeError<$typename>> {
+                    negafib_bits_from_table(self < 0, self.unsigned_abs(), NEGAFIB_TABLE, sink)
+                        .map_err(|e| map_negafib_error(self, e))
+                }
+            }
+
+            impl<T> NegafibEncode<$typename> for T
+            where
+                T: IntoIterator + Debug + Send + Sync,
+                T::Item: EncodeElement<Integer = $typename>,
+            {
+                fn negafib_encode_into<S: BitSink>(
+                    self,
+                    sink: &mut S,
+                ) -> Result<(), ElementEncodeError<$typename>> {
+                    for (i, elt) in self.into_iter().enumerate() {
+                        match elt.borrow().negafib_encode_into(sink) {
+                            Ok(_) => {}
+                            Err(e) => {
+                                return Err(ElementEncodeError { index: i, error: e });
+                            }
+                        }
+                    }
+                    Ok(())
+                }
+            }
+
+            #[doc = "An iterator that yields negafibonacci-decoded `"]
             #[doc = $typename_str ]
             #[doc = "` integers."]
-            pub struct $decoder_name<I> {
+            pub struct $negafib_decoder_name<I> {
                 pub(crate) orig: I,
             }
 
-            impl<I: Iterator<Item = bool>> Iterator for $decoder_name<I> {
+            impl<I: BitSource> Iterator for $negafib_decoder_name<I> {
                 #[doc = "This iterator yields `Ok("]
                 #[doc = $typename_str]
                 #[doc = ")` when a number could be decoded successfully and returns an error"]
@@ -62,7 +454,21 @@ macro_rules! impl_fib_encode_for_integral_type {
                 type Item = Result<$typename, DecodeError>;
 
                 fn next(&mut self) -> Option<Self::Item> {
-                    decode_from(&mut self.orig, TABLE)
+                    negafib_decode_from(
+                        &mut self.orig,
+                        NEGAFIB_TABLE,
+                        <$typename>::MAX as $unsigned,
+                        <$typename>::MIN.unsigned_abs(),
+                    )
+                    .map(|res| {
+                        res.map(|(negative, magnitude)| {
+                            if negative {
+                                (magnitude as $typename).wrapping_neg()
+                            } else {
+                                magnitude as $typename
+                            }
+                        })
+                    })
                 }
             }
         }
@@ -80,9 +486,59 @@ macro_rules! impl_decoder {
             T: IntoIterator<Item = bool, IntoIter = I>,
             I: Iterator<Item = bool>,
         {
-            super::$typename::DecodeIter {
+            crate::decode::fib_decode(collection)
+        }
+    };
+}
+
+macro_rules! impl_negafib_decoder {
+    ($typename:ident, $typename_str:expr, $decode_name:ident) => {
+        #[doc = "Returns an iterator that consumes bits (`bool`) and negafibonacci-decodes"]
+        #[doc = "them into `"]
+        #[doc = $typename_str]
+        #[doc = "` integers."]
+        pub fn $decode_name<T, I>(collection: T) -> super::$typename::NegafibDecodeIter<I>
+        where
+            T: IntoIterator<Item = bool, IntoIter = I>,
+            I: Iterator<Item = bool>,
+        {
+            super::$typename::NegafibDecodeIter {
                 orig: collection.into_iter(),
             }
         }
     };
 }
+
+macro_rules! impl_zero_decoder {
+    ($typename:ident, $typename_str:expr, $decode_name:ident) => {
+        #[doc = "Returns an iterator that consumes bits (`bool`) and fibonacci-decodes them"]
+        #[doc = "into `"]
+        #[doc = $typename_str]
+        #[doc = "` integers, each offset by one so that zero can be represented."]
+        pub fn $decode_name<T, I>(collection: T) -> super::$typename::DecodeWithZeroIter<I>
+        where
+            T: IntoIterator<Item = bool, IntoIter = I>,
+            I: Iterator<Item = bool>,
+        {
+            super::$typename::DecodeWithZeroIter {
+                orig: collection.into_iter(),
+            }
+        }
+    };
+}
+
+macro_rules! impl_nonzero_decoder {
+    ($typename:ident, $nonzero:ident, $decode_name:ident) => {
+        #[doc = "Returns an iterator that consumes bits (`bool`) and fibonacci-decodes them"]
+        #[doc = "into `"]
+        #[doc = stringify!($nonzero)]
+        #[doc = "` integers."]
+        pub fn $decode_name<T, I>(collection: T) -> super::$typename::NonZeroDecodeIter<I>
+        where
+            T: IntoIterator<Item = bool, IntoIter = I>,
+            I: Iterator<Item = bool>,
+        {
+            crate::decode::fib_decode(collection)
+        }
+    };
+}
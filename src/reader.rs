@@ -0,0 +1,103 @@
+use crate::bit_order::BitOrder;
+use crate::decode::DecodeError;
+use crate::incremental::{FibDecoder, IncrementalDecode};
+use std::io::{self, Read};
+
+/// The number of bytes a [`FibReader`] reads from its inner reader at
+/// a time.
+const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Fibonacci-decodes numbers of type `T` from an
+/// [`io::Read`](std::io::Read)er, reading its bytes as they are
+/// needed.
+///
+/// The bits of each byte are read most significant bit first, the
+/// same order that `BitVec::to_bytes` and [`FibWriter`] use, unless
+/// the reader is constructed with
+/// [`with_bit_order`](FibReader::with_bit_order). The bytes are
+/// decoded by a [`FibDecoder`], so the reader yields the same results
+/// as [`DecodeIter`] does, each wrapped in `Ok`.
+///
+/// When reading from the inner reader fails, the reader yields the
+/// I/O error as `Err`, so a failing reader can be told apart from
+/// both the end of the data and a malformed code word. The partially
+/// decoded code word is kept, and the next call to `next` tries
+/// reading again, continuing where the failed read left off.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{FibReader, FibWriter};
+///
+/// let mut writer = FibWriter::new(Vec::new());
+/// writer.extend(vec![1_u32, 50, 3003]);
+/// let bytes = writer.finish().unwrap();
+///
+/// let reader = FibReader::<_, u32>::new(&bytes[..]);
+/// let decoded: Vec<u32> = reader.map(|n| n.unwrap().unwrap()).collect();
+/// assert_eq!(vec![1, 50, 3003], decoded);
+/// ```
+///
+/// [`FibWriter`]: crate::FibWriter
+/// [`DecodeIter`]: crate::DecodeIter
+#[derive(Debug)]
+pub struct FibReader<R, T: IncrementalDecode> {
+    inner: R,
+    buffer: Box<[u8]>,
+    decoder: FibDecoder<T>,
+    done: bool,
+}
+
+impl<R: Read, T: IncrementalDecode> FibReader<R, T> {
+    /// Returns a reader that decodes numbers from the bytes of
+    /// `inner`.
+    pub fn new(inner: R) -> FibReader<R, T> {
+        FibReader::with_bit_order(inner, BitOrder::MsbFirst)
+    }
+
+    /// Returns a reader that decodes numbers from the bytes of
+    /// `inner`, reading the bits of each byte in the given order.
+    pub fn with_bit_order(inner: R, order: BitOrder) -> FibReader<R, T> {
+        FibReader {
+            inner,
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            decoder: FibDecoder::with_bit_order(order),
+            done: false,
+        }
+    }
+
+    /// Returns the inner reader. Any numbers decoded from the bytes
+    /// read from it, but not yielded yet, are lost, and so is a
+    /// partially decoded code word.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read, T: IncrementalDecode> Iterator for FibReader<R, T> {
+    /// This iterator yields `Ok(Ok(T))` when a number could be decoded
+    /// successfully, `Ok(Err(_))` when its code word is malformed, and
+    /// `Err(_)` when reading from the inner reader fails.
+    type Item = io::Result<Result<T, DecodeError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(result) = self.decoder.drain().next() {
+                return Some(Ok(result));
+            }
+            if self.done {
+                return None;
+            }
+            match self.inner.read(&mut self.buffer) {
+                Ok(0) => {
+                    self.done = true;
+                    if let Err(e) = self.decoder.finish() {
+                        return Some(Ok(Err(e)));
+                    }
+                }
+                Ok(n) => self.decoder.push_bytes(&self.buffer[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
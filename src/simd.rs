@@ -0,0 +1,101 @@
+//! Finding the candidates for the ends of code words in many words at
+//! once, with x86_64 SIMD instructions.
+
+use std::arch::x86_64::{
+    __m128i, __m256i, _mm256_and_si256, _mm256_loadu_si256, _mm256_slli_epi64, _mm256_storeu_si256,
+    _mm_and_si128, _mm_loadu_si128, _mm_slli_epi64, _mm_storeu_si128,
+};
+
+/// The number of words that [`pairs`] is handed at most at once.
+pub(crate) const BLOCK_WORDS: usize = 32;
+
+/// Sets each of `pairs` to the mask of the set bits of the
+/// corresponding one of `words` that follow another set bit in the
+/// same word, bits being read from the least significant one up.
+///
+/// Uses AVX2 instructions on 256-bit lanes of four words, or SSE2
+/// instructions on 128-bit lanes of two words, if the CPU supports
+/// them, and plain shifts otherwise.
+#[inline]
+pub(crate) fn pairs(words: &[u64], pairs: &mut [u64]) {
+    debug_assert_eq!(words.len(), pairs.len());
+    if is_x86_feature_detected!("avx2") {
+        // SAFETY: the CPU supports AVX2.
+        unsafe { pairs_avx2(words, pairs) }
+    } else if is_x86_feature_detected!("sse2") {
+        // SAFETY: the CPU supports SSE2.
+        unsafe { pairs_sse2(words, pairs) }
+    } else {
+        pairs_scalar(words, pairs)
+    }
+}
+
+#[target_feature(enable = "avx2")]
+unsafe fn pairs_avx2(words: &[u64], pairs: &mut [u64]) {
+    let mut words = words.chunks_exact(4);
+    let mut pairs = pairs.chunks_exact_mut(4);
+    for (words, pairs) in (&mut words).zip(&mut pairs) {
+        // The unaligned loads and stores access exactly the 256 bits
+        // of the chunks:
+        let x = _mm256_loadu_si256(words.as_ptr() as *const __m256i);
+        let x = _mm256_and_si256(x, _mm256_slli_epi64(x, 1));
+        _mm256_storeu_si256(pairs.as_mut_ptr() as *mut __m256i, x);
+    }
+    pairs_scalar(words.remainder(), pairs.into_remainder());
+}
+
+#[target_feature(enable = "sse2")]
+unsafe fn pairs_sse2(words: &[u64], pairs: &mut [u64]) {
+    let mut words = words.chunks_exact(2);
+    let mut pairs = pairs.chunks_exact_mut(2);
+    for (words, pairs) in (&mut words).zip(&mut pairs) {
+        // The unaligned loads and stores access exactly the 128 bits
+        // of the chunks:
+        let x = _mm_loadu_si128(words.as_ptr() as *const __m128i);
+        let x = _mm_and_si128(x, _mm_slli_epi64(x, 1));
+        _mm_storeu_si128(pairs.as_mut_ptr() as *mut __m128i, x);
+    }
+    pairs_scalar(words.remainder(), pairs.into_remainder());
+}
+
+fn pairs_scalar(words: &[u64], pairs: &mut [u64]) {
+    for (pair, word) in pairs.iter_mut().zip(words) {
+        *pair = word & word << 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn avx2_pairs_like_scalar(ref words in proptest::collection::vec(any::<u64>(),
+                                                                         0..=BLOCK_WORDS)) {
+            if !is_x86_feature_detected!("avx2") {
+                return Ok(());
+            }
+            let mut expected = vec![0; words.len()];
+            pairs_scalar(words, &mut expected);
+            let mut actual = vec![0; words.len()];
+            // SAFETY: the CPU supports AVX2.
+            unsafe { pairs_avx2(words, &mut actual) };
+            prop_assert_eq!(expected, actual);
+        }
+
+        #[test]
+        fn sse2_pairs_like_scalar(ref words in proptest::collection::vec(any::<u64>(),
+                                                                         0..=BLOCK_WORDS)) {
+            if !is_x86_feature_detected!("sse2") {
+                return Ok(());
+            }
+            let mut expected = vec![0; words.len()];
+            pairs_scalar(words, &mut expected);
+            let mut actual = vec![0; words.len()];
+            // SAFETY: the CPU supports SSE2.
+            unsafe { pairs_sse2(words, &mut actual) };
+            prop_assert_eq!(expected, actual);
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use crate::bit_order::BitOrder;
+use bit_vec::BitVec;
+
+/// A growable sequence of bits that fibonacci code words can be
+/// appended to.
+///
+/// This crate implements this trait for `BitVec` and for
+/// [`ByteSink`], and, with the `bitvec` feature enabled, for the
+/// `bitvec` crate's `BitVec<T, O>`. Implementing it for other buffers,
+/// e.g. ring buffers or memory-mapped regions, lets the encoding
+/// traits' `*_into` methods write to them directly.
+///
+/// Encoders append zero bits with [`push_zeros`](BitSink::push_zeros)
+/// and then set the bits of the code word that are 1 with
+/// [`set_bit`](BitSink::set_bit); they only ever set bits they
+/// appended themselves. Short code words whose bits are known up front
+/// are appended all at once with [`push_bits`](BitSink::push_bits).
+/// When encoding fails, they use [`truncate`](BitSink::truncate) to
+/// remove the bits of the number that failed.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{BitSink, Encode};
+///
+/// /// Collects the bits as characters.
+/// #[derive(Default)]
+/// struct Chars(Vec<char>);
+///
+/// impl BitSink for Chars {
+///     fn len(&self) -> usize {
+///         self.0.len()
+///     }
+///
+///     fn push_zeros(&mut self, n: usize) {
+///         let len = self.0.len();
+///         self.0.resize(len + n, '0');
+///     }
+///
+///     fn set_bit(&mut self, i: usize) {
+///         self.0[i] = '1';
+///     }
+///
+///     fn truncate(&mut self, len: usize) {
+///         self.0.truncate(len);
+///     }
+/// }
+///
+/// let mut sink = Chars::default();
+/// vec![1_u32, 50].fib_encode_into(&mut sink).unwrap();
+/// assert_eq!("11001001011", sink.0.into_iter().collect::<String>());
+/// ```
+pub trait BitSink {
+    /// Returns the number of bits in the sink.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the sink holds no bits.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends `n` zero bits to the end of the sink.
+    fn push_zeros(&mut self, n: usize);
+
+    /// Sets the bit at position `i`, which must be less than
+    /// [`len`](BitSink::len), to 1.
+    fn set_bit(&mut self, i: usize);
+
+    /// Shortens the sink to `len` bits.
+    fn truncate(&mut self, len: usize);
+
+    /// Appends the `n` (at most 64) least significant bits of `bits`,
+    /// the least significant one first.
+    ///
+    /// The default implementation calls
+    /// [`push_zeros`](BitSink::push_zeros) and then
+    /// [`set_bit`](BitSink::set_bit) for each bit that is 1; sinks that
+    /// store their bits in words or bytes can implement it more
+    /// efficiently.
+    fn push_bits(&mut self, bits: u64, n: u32) {
+        debug_assert!(n <= 64);
+        let start = self.len();
+        self.push_zeros(n as usize);
+        let mut rest = if n < 64 { bits & ((1 << n) - 1) } else { bits };
+        while rest != 0 {
+            self.set_bit(start + rest.trailing_zeros() as usize);
+            rest &= rest - 1;
+        }
+    }
+}
+
+impl BitSink for BitVec {
+    #[inline]
+    fn len(&self) -> usize {
+        BitVec::len(self)
+    }
+
+    #[inline]
+    fn push_zeros(&mut self, n: usize) {
+        self.grow(n, false);
+    }
+
+    #[inline]
+    fn set_bit(&mut self, i: usize) {
+        self.set(i, true);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        BitVec::truncate(self, len);
+    }
+}
+
+#[cfg(feature = "bitvec")]
+impl<T, O> BitSink for bitvec::vec::BitVec<T, O>
+where
+    T: bitvec::store::BitStore,
+    O: bitvec::order::BitOrder,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        bitvec::slice::BitSlice::len(self)
+    }
+
+    #[inline]
+    fn push_zeros(&mut self, n: usize) {
+        let len = bitvec::slice::BitSlice::len(self);
+        self.resize(len + n, false);
+    }
+
+    #[inline]
+    fn set_bit(&mut self, i: usize) {
+        self.set(i, true);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        bitvec::vec::BitVec::truncate(self, len);
+    }
+}
+
+/// A bit sink that writes its bits straight into a `Vec<u8>`.
+///
+/// By default, the bits of each byte are stored most significant bit
+/// first, and the last byte is padded with zero bits: the same layout
+/// that `BitVec::to_bytes` produces, but without building a `BitVec`
+/// first. [`with_bit_order`](ByteSink::with_bit_order) returns a sink
+/// that stores them in another [`BitOrder`].
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{ByteSink, Encode, EncodeOne};
+///
+/// let mut sink = ByteSink::new();
+/// 1_u32.fib_encode_into(&mut sink).unwrap();
+/// vec![50_u32, 3003].fib_encode_into(&mut sink).unwrap();
+/// assert_eq!(
+///     vec![1_u32, 50, 3003].fib_encode().unwrap().to_bytes(),
+///     sink.into_bytes()
+/// );
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ByteSink {
+    bytes: Vec<u8>,
+    len: usize,
+    order: BitOrder,
+}
+
+impl ByteSink {
+    /// Returns an empty byte sink.
+    pub fn new() -> ByteSink {
+        ByteSink::default()
+    }
+
+    /// Returns an empty byte sink that stores bits in the given
+    /// order.
+    pub fn with_bit_order(order: BitOrder) -> ByteSink {
+        ByteSink {
+            order,
+            ..ByteSink::default()
+        }
+    }
+
+    /// Returns a sink that appends bits after the given bytes, storing
+    /// them in the given order. The bytes should have been written in
+    /// that order, too.
+    pub fn from_bytes_with_order(bytes: Vec<u8>, order: BitOrder) -> ByteSink {
+        let len = bytes.len() * 8;
+        ByteSink { bytes, len, order }
+    }
+
+    /// Returns the order in which the sink stores bits within each
+    /// byte.
+    pub fn bit_order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Returns the number of bits written to the sink.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no bits have been written to the sink.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bytes written so far, with the last byte padded
+    /// with zero bits.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the bytes written to the sink, with the last byte
+    /// padded with zero bits.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns the bytes whose bits have all been written.
+    pub(crate) fn complete_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len / 8]
+    }
+
+    /// Removes the first `n` bytes, which must be complete.
+    pub(crate) fn consume_bytes(&mut self, n: usize) {
+        self.bytes.drain(..n);
+        self.len -= n * 8;
+    }
+
+    /// Pads the bits written so far with zero bits to a complete
+    /// byte.
+    pub(crate) fn pad_to_byte(&mut self) {
+        self.len = self.bytes.len() * 8;
+    }
+}
+
+impl From<Vec<u8>> for ByteSink {
+    /// Returns a sink that appends bits after the given bytes, most
+    /// significant bit first.
+    fn from(bytes: Vec<u8>) -> ByteSink {
+        ByteSink::from_bytes_with_order(bytes, BitOrder::MsbFirst)
+    }
+}
+
+impl BitSink for ByteSink {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn push_zeros(&mut self, n: usize) {
+        self.len += n;
+        self.bytes.resize(self.len.div_ceil(8), 0);
+    }
+
+    #[inline]
+    fn set_bit(&mut self, i: usize) {
+        self.bytes[i / 8] |= self.order.mask((i % 8) as u32);
+    }
+
+    fn push_bits(&mut self, bits: u64, n: u32) {
+        debug_assert!(n <= 64);
+        let mut rest = if n < 64 { bits & ((1 << n) - 1) } else { bits };
+        let mut left = n;
+        while left > 0 {
+            let used = (self.len % 8) as u32;
+            if used == 0 {
+                self.bytes.push(0);
+            }
+            let taken = left.min(8 - used);
+            let chunk = (rest & ((1 << taken) - 1)) as u8;
+            *self.bytes.last_mut().unwrap() |= match self.order {
+                BitOrder::LsbFirst => chunk << used,
+                BitOrder::MsbFirst => chunk.reverse_bits() >> used,
+            };
+            rest = rest.checked_shr(taken).unwrap_or(0);
+            left -= taken;
+            self.len += taken as usize;
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        self.len = len;
+        self.bytes.truncate(len.div_ceil(8));
+        let used_bits = (len % 8) as u32;
+        if used_bits > 0 {
+            // Keep the padding bits of the last byte zero:
+            if let Some(last) = self.bytes.last_mut() {
+                *last &= self.order.leading_mask(used_bits);
+            }
+        }
+    }
+}
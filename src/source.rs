@@ -0,0 +1,144 @@
+/// A sequence of bits that fibonacci code words can be decoded from.
+///
+/// Every iterator over `bool`s is a bit source, so the bits of a
+/// `BitVec`, a [`ByteBits`](crate::ByteBits) iterator or any other
+/// bit iterator can be decoded. Buffers that aren't iterators, e.g.
+/// ring buffers or memory-mapped regions, can implement this trait
+/// directly and be decoded via [`fib_decode_source`].
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{fib_decode_source, BitSource, Encode};
+///
+/// /// Reads bits from a buffer of `u64` words, least significant bit
+/// /// first.
+/// struct Words<'a> {
+///     words: &'a [u64],
+///     pos: usize,
+///     len: usize,
+/// }
+///
+/// impl<'a> BitSource for Words<'a> {
+///     fn next_bit(&mut self) -> Option<bool> {
+///         if self.pos == self.len {
+///             return None;
+///         }
+///         let bit = self.words[self.pos / 64] >> (self.pos % 64) & 1 == 1;
+///         self.pos += 1;
+///         Some(bit)
+///     }
+///
+///     fn next_word(&mut self, n: u32) -> (u64, u32) {
+///         let count = n.min((self.len - self.pos) as u32);
+///         if count == 0 {
+///             return (0, 0);
+///         }
+///         let (i, shift) = (self.pos / 64, self.pos % 64);
+///         let mut word = self.words[i] >> shift;
+///         if shift > 0 && i + 1 < self.words.len() {
+///             word |= self.words[i + 1] << (64 - shift);
+///         }
+///         if count < 64 {
+///             word &= (1 << count) - 1;
+///         }
+///         self.pos += count as usize;
+///         (word, count)
+///     }
+/// }
+///
+/// let bits = vec![1_u32, 50, 3003].fib_encode().unwrap();
+/// let mut words = vec![0_u64; bits.len().div_ceil(64)];
+/// for (i, bit) in bits.iter().enumerate() {
+///     words[i / 64] |= (bit as u64) << (i % 64);
+/// }
+///
+/// let source = Words { words: &words, pos: 0, len: bits.len() };
+/// let decoded: Vec<u32> = fib_decode_source(source).map(|n| n.unwrap()).collect();
+/// assert_eq!(vec![1, 50, 3003], decoded);
+/// ```
+///
+/// [`fib_decode_source`]: crate::fib_decode_source
+pub trait BitSource {
+    /// Returns the next bit, or `None` at the end of the source.
+    fn next_bit(&mut self) -> Option<bool>;
+
+    /// Reads up to `n` bits (at most 64) at once. Returns them as a
+    /// word in which the first bit read is the least significant one,
+    /// along with the number of bits read, which is less than `n` only
+    /// at the end of the source.
+    ///
+    /// The default implementation calls
+    /// [`next_bit`](BitSource::next_bit) for each bit; sources that
+    /// store their bits in words can implement it more efficiently.
+    /// [`fib_decode_source`] reads its source a word at a time.
+    ///
+    /// [`fib_decode_source`]: crate::fib_decode_source
+    fn next_word(&mut self, n: u32) -> (u64, u32) {
+        debug_assert!(n <= 64);
+        let mut word = 0;
+        for i in 0..n {
+            match self.next_bit() {
+                Some(bit) => word |= (bit as u64) << i,
+                None => return (word, i),
+            }
+        }
+        (word, n)
+    }
+}
+
+impl<I> BitSource for I
+where
+    I: Iterator<Item = bool> + ?Sized,
+{
+    #[inline]
+    fn next_bit(&mut self) -> Option<bool> {
+        self.next()
+    }
+}
+
+/// A [`BitSource`] that reads the bits of another one a word at a
+/// time, via [`next_word`](BitSource::next_word), and hands them out
+/// one by one. [`fib_decode_source`] decodes through it.
+///
+/// [`fib_decode_source`]: crate::fib_decode_source
+#[derive(Debug)]
+pub struct WordBits<S> {
+    source: S,
+    word: u64,
+    left: u32,
+}
+
+impl<S: BitSource> WordBits<S> {
+    /// Returns a bit source that reads the bits of `source`.
+    pub fn new(source: S) -> WordBits<S> {
+        WordBits {
+            source,
+            word: 0,
+            left: 0,
+        }
+    }
+
+    /// Returns the underlying bit source. Up to 63 bits that were
+    /// read from it, but not handed out yet, are lost.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: BitSource> BitSource for WordBits<S> {
+    #[inline]
+    fn next_bit(&mut self) -> Option<bool> {
+        if self.left == 0 {
+            let (word, count) = self.source.next_word(64);
+            if count == 0 {
+                return None;
+            }
+            self.word = word;
+            self.left = count;
+        }
+        let bit = self.word & 1 == 1;
+        self.word >>= 1;
+        self.left -= 1;
+        Some(bit)
+    }
+}
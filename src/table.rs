@@ -0,0 +1,67 @@
+#[cfg(feature = "bigint")]
+use crate::biguint::MAX_CODE_WORD_LEN;
+#[cfg(feature = "bigint")]
+use num::BigUint;
+#[cfg(feature = "bigint")]
+use std::mem;
+
+/// A table of fibonacci numbers (1, 2, 3, 5, ...) that decoding looks
+/// up elements in.
+///
+/// The tables for primitive integers are `'static` slices generated by
+/// the build script; arbitrary-precision integers compute their
+/// elements as decoding reaches them instead.
+pub(crate) trait FibonacciTable<T> {
+    /// Returns the element at `index`, or `None` if the table ends
+    /// before that index.
+    fn get(&mut self, index: usize) -> Option<&T>;
+}
+
+impl<T> FibonacciTable<T> for &[T] {
+    #[inline]
+    fn get(&mut self, index: usize) -> Option<&T> {
+        <[T]>::get(self, index)
+    }
+}
+
+/// The fibonacci numbers that decoding a code word needs, computed as
+/// it reaches their positions. Since the positions only go up within a
+/// code word, only the current element and the next one are kept,
+/// rather than a table of all of them.
+#[cfg(feature = "bigint")]
+#[derive(Debug)]
+pub struct FibonacciPair {
+    index: usize,
+    current: BigUint,
+    next: BigUint,
+}
+
+#[cfg(feature = "bigint")]
+impl Default for FibonacciPair {
+    fn default() -> Self {
+        FibonacciPair {
+            index: 0,
+            current: BigUint::from(1u8),
+            next: BigUint::from(2u8),
+        }
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl FibonacciTable<BigUint> for &mut FibonacciPair {
+    fn get(&mut self, index: usize) -> Option<&BigUint> {
+        if index >= MAX_CODE_WORD_LEN {
+            return None;
+        }
+        if index < self.index {
+            // A new code word starts over:
+            **self = FibonacciPair::default();
+        }
+        while self.index < index {
+            let next = &self.current + &self.next;
+            self.current = mem::replace(&mut self.next, next);
+            self.index += 1;
+        }
+        Some(&self.current)
+    }
+}
@@ -0,0 +1,163 @@
+use crate::bit_order::BitOrder;
+use crate::encode::EncodeOne;
+use crate::sink::ByteSink;
+use failure::Fail;
+use std::io::{self, Write};
+
+/// The number of complete bytes a [`FibWriter`] buffers before
+/// writing them to the inner writer.
+pub(crate) const BUFFER_SIZE: usize = 8 * 1024;
+
+/// Fibonacci-encodes numbers one at a time into an
+/// [`io::Write`](std::io::Write)r.
+///
+/// The bytes written are the same as those that
+/// `fib_encode().to_bytes()` returns for all the numbers together,
+/// unless the writer is constructed with
+/// [`with_bit_order`](FibWriter::with_bit_order).
+/// Since code words don't end on byte boundaries, the writer buffers
+/// the bits that don't make up a complete byte yet; [`finish`] pads
+/// them to a byte with zero bits and writes them out. Dropping a
+/// `FibWriter` without calling [`finish`] loses any buffered bits.
+///
+/// Encoding errors are reported as [`io::Error`]s of kind
+/// [`InvalidInput`](io::ErrorKind::InvalidInput), wrapping the
+/// encoding error.
+///
+/// # Example
+/// ``` rust
+/// use fibonacci_codec::{Encode, FibWriter};
+///
+/// let mut writer = FibWriter::new(Vec::new());
+/// writer.write_value(1_u32).unwrap();
+/// writer.extend(vec![50_u32, 3003]);
+/// let bytes = writer.finish().unwrap();
+/// assert_eq!(vec![1_u32, 50, 3003].fib_encode().unwrap().to_bytes(), bytes);
+/// ```
+///
+/// [`finish`]: FibWriter::finish
+#[derive(Debug)]
+pub struct FibWriter<W: Write> {
+    inner: W,
+    buffer: ByteSink,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> FibWriter<W> {
+    /// Returns a writer that writes encoded numbers to `inner`.
+    pub fn new(inner: W) -> FibWriter<W> {
+        FibWriter::with_bit_order(inner, BitOrder::MsbFirst)
+    }
+
+    /// Returns a writer that writes encoded numbers to `inner`,
+    /// storing their bits in the given order within each byte.
+    pub fn with_bit_order(inner: W, order: BitOrder) -> FibWriter<W> {
+        FibWriter {
+            inner,
+            buffer: ByteSink::with_bit_order(order),
+            error: None,
+        }
+    }
+
+    /// Fibonacci-encodes `value` into the buffer, first writing all
+    /// complete bytes if enough of them are buffered.
+    ///
+    /// # Errors
+    /// Returns an error if `value` can't be encoded, if writing to the
+    /// inner writer fails, or if an earlier call to `extend` failed.
+    /// In all of these cases, none of the value's bits are buffered,
+    /// so it can be written again.
+    pub fn write_value<T>(&mut self, value: T) -> io::Result<()>
+    where
+        T: EncodeOne,
+        T::Error: Fail,
+    {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        if self.buffer.complete_bytes().len() >= BUFFER_SIZE {
+            self.write_complete_bytes()?;
+        }
+        value
+            .fib_encode_into(&mut self.buffer)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.compat()))
+    }
+
+    /// Writes all complete bytes buffered so far to the inner writer,
+    /// and flushes it. The bits that don't make up a complete byte
+    /// stay buffered.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.write_complete_bytes()?;
+        self.inner.flush()
+    }
+
+    /// Pads the buffered bits with zero bits to a complete byte,
+    /// writes all buffered bytes and returns the inner writer.
+    ///
+    /// # Errors
+    /// Returns an error if writing to the inner writer fails, or the
+    /// error that a call to `extend` ran into.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.buffer.pad_to_byte();
+        self.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Writes all complete bytes buffered so far to the inner writer.
+    /// Like `io::BufWriter`, this removes the bytes that were written
+    /// from the buffer even if a later write fails, so that they
+    /// aren't written twice.
+    fn write_complete_bytes(&mut self) -> io::Result<()> {
+        let mut written = 0;
+        let result = loop {
+            let remaining = &self.buffer.complete_bytes()[written..];
+            if remaining.is_empty() {
+                break Ok(());
+            }
+            match self.inner.write(remaining) {
+                Ok(0) => {
+                    break Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the buffered bytes",
+                    ));
+                }
+                Ok(n) => written += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => break Err(e),
+            }
+        };
+        self.buffer.consume_bytes(written);
+        result
+    }
+}
+
+impl<W, T> Extend<T> for FibWriter<W>
+where
+    W: Write,
+    T: EncodeOne,
+    T::Error: Fail,
+{
+    /// Writes each number of the iterator, like
+    /// [`write_value`](FibWriter::write_value) does. Since `extend`
+    /// can't return errors, it stops at the first one; the next call
+    /// to `write_value` or [`finish`](FibWriter::finish) returns it.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        if self.error.is_some() {
+            return;
+        }
+        for value in iter {
+            if let Err(e) = self.write_value(value) {
+                self.error = Some(e);
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "bigint")]
+
+use bit_vec::BitVec;
+use fibonacci_codec::biguint::MAX_CODE_WORD_LEN;
+use fibonacci_codec::{
+    fib_decode, fib_decode_biguint, fib_decode_terminated, fib_decode_u64, DecodeError, Encode,
+    EncodeError, EncodeOne, FibReader,
+};
+use num::{BigUint, One, Zero};
+use proptest::prelude::*;
+
+#[test]
+fn test_same_as_primitive() {
+    let numbers: Vec<u64> = vec![1, 2, 14, 65, 3003, u64::MAX];
+    let big: Vec<BigUint> = numbers.iter().map(|n| BigUint::from(*n)).collect();
+    assert_eq!(
+        numbers.clone().fib_encode().unwrap(),
+        big.fib_encode().unwrap()
+    );
+}
+
+#[test]
+fn test_roundtrip_huge() {
+    let input: Vec<BigUint> = vec![
+        BigUint::one(),
+        BigUint::one() << 200,
+        (BigUint::one() << 1000) - BigUint::one(),
+        BigUint::from(10u8).pow(100),
+    ];
+    let encoded = input.clone().fib_encode().unwrap();
+    let decoded: Vec<BigUint> = fib_decode_biguint(&encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    // Decoding these as u64 fails for all but the first number:
+    let decoded: Vec<bool> = fib_decode_u64(&encoded).map(|x| x.is_ok()).collect();
+    assert_eq!(vec![true, false, false, false], decoded);
+}
+
+#[test]
+fn test_encode_references() {
+    let input: Vec<BigUint> = vec![BigUint::one(), BigUint::one() << 200];
+    let encoded = input.clone().fib_encode().unwrap();
+    assert_eq!(encoded, (&input).fib_encode().unwrap());
+    assert_eq!(encoded, input[..].iter().fib_encode().unwrap());
+    assert_eq!(
+        input.clone().fib_encode_terminated().unwrap(),
+        (&input).fib_encode_terminated().unwrap()
+    );
+}
+
+#[test]
+fn test_long_zero_run() {
+    // 32 KB of zero bits, then a terminator, then the number 5:
+    let mut bits = BitVec::from_elem(32 * 1024 * 8, false);
+    bits.push(true);
+    bits.push(true);
+    bits.extend(&BigUint::from(5u8).fib_encode().unwrap());
+    let decoded: Vec<Result<BigUint, DecodeError>> = fib_decode_biguint(&bits).collect();
+    assert_eq!(
+        vec![
+            Err(DecodeError::FibonacciElementOverflow {
+                bit_pos: MAX_CODE_WORD_LEN
+            }),
+            Ok(BigUint::from(5u8))
+        ],
+        decoded
+    );
+
+    // Each value gets decoded the same way via the Decode trait:
+    let decoded: Vec<Result<BigUint, DecodeError>> = fib_decode(&bits).collect();
+    assert_eq!(2, decoded.len());
+    assert!(decoded[0].is_err());
+}
+
+/// Returns the fibonacci number at `index` in the table decoding uses,
+/// which starts 1, 2, 3, 5, ...
+fn fibonacci(index: usize) -> BigUint {
+    let (mut low, mut high) = (BigUint::one(), BigUint::from(2u8));
+    for _ in 0..index {
+        let next = &low + &high;
+        low = std::mem::replace(&mut high, next);
+    }
+    low
+}
+
+#[test]
+fn test_max_code_word_len() {
+    // The largest number whose code word is MAX_CODE_WORD_LEN bits
+    // long, plus the terminating one:
+    let largest = fibonacci(MAX_CODE_WORD_LEN) - BigUint::one();
+    let encoded = largest.clone().fib_encode().unwrap();
+    assert_eq!(MAX_CODE_WORD_LEN + 1, encoded.len());
+    let decoded: Vec<Result<BigUint, DecodeError>> = fib_decode_biguint(&encoded).collect();
+    assert_eq!(vec![Ok(largest.clone())], decoded);
+
+    let too_large = largest + BigUint::one();
+    assert_eq!(
+        Err(EncodeError::ValueTooLarge(too_large.clone())),
+        too_large.fib_encode()
+    );
+    let huge: BigUint = BigUint::one() << 50000;
+    assert_eq!(
+        Err(EncodeError::ValueTooLarge(huge.clone())),
+        huge.fib_encode()
+    );
+
+    // The error points at the element that is too large:
+    let err = vec![BigUint::one(), BigUint::one() << 50000]
+        .fib_encode()
+        .unwrap_err();
+    assert_eq!(1, err.index);
+}
+
+#[test]
+fn test_zero() {
+    assert_eq!(
+        Err(EncodeError::ValueTooSmall(BigUint::zero())),
+        BigUint::zero().fib_encode()
+    );
+}
+
+#[test]
+fn test_terminated() {
+    let input: Vec<BigUint> = vec![BigUint::one(), (BigUint::one() << 300) - BigUint::one()];
+    let encoded = input.clone().fib_encode_terminated().unwrap();
+    let decoded: Vec<BigUint> = fib_decode_terminated(&encoded)
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+    assert!(vec![BigUint::zero()].fib_encode_terminated().is_err());
+}
+
+#[test]
+fn test_reader() {
+    let input: Vec<BigUint> = vec![BigUint::one(), (BigUint::one() << 300) - BigUint::one()];
+    let bytes = input.clone().fib_encode_bytes().unwrap();
+    let decoded: Vec<BigUint> = FibReader::<_, BigUint>::new(&bytes[..])
+        .map(|x| x.unwrap().unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+}
+
+proptest! {
+    #[test]
+    fn roundtrips_biguint(ref digits in proptest::collection::vec(any::<u32>(), 1..20)) {
+        let input = BigUint::new(digits.clone()) + BigUint::one();
+        let bits = input.clone().fib_encode().expect("Expected an Ok result");
+        let decoded: Vec<BigUint> = fib_decode_biguint(&bits).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, vec![input]);
+    }
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "bitvec")]
+
+use bitvec::prelude::*;
+use fibonacci_codec::{
+    fib_decode_bitslice, BitOrder, DecodeError, ElementEncodeError, Encode, EncodeError, EncodeOne,
+    EncodeWithZero, NegafibEncode,
+};
+
+#[test]
+fn test_respects_order() {
+    let input: Vec<u16> = vec![1, 50, 3003];
+
+    let mut msb0: BitVec<u8, Msb0> = BitVec::new();
+    input.clone().fib_encode_into(&mut msb0).unwrap();
+    assert_eq!(
+        input
+            .clone()
+            .fib_encode_bytes_with_order(BitOrder::MsbFirst)
+            .unwrap(),
+        msb0.as_raw_slice()
+    );
+
+    let mut lsb0: BitVec<u8, Lsb0> = BitVec::new();
+    input.clone().fib_encode_into(&mut lsb0).unwrap();
+    assert_eq!(
+        input
+            .clone()
+            .fib_encode_bytes_with_order(BitOrder::LsbFirst)
+            .unwrap(),
+        lsb0.as_raw_slice()
+    );
+
+    let decoded: Vec<u16> = fib_decode_bitslice(&msb0).map(|n| n.unwrap()).collect();
+    assert_eq!(input, decoded);
+    let decoded: Vec<u16> = fib_decode_bitslice(&lsb0).map(|n| n.unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_same_as_bit_vec() {
+    let input: Vec<i64> = vec![0, -1, 70, i64::MIN, i64::MAX];
+    let expected = input.clone().fib_encode().unwrap();
+    let mut bits: BitVec<u32, Lsb0> = BitVec::new();
+    input.clone().fib_encode_into(&mut bits).unwrap();
+    assert_eq!(
+        expected.iter().collect::<Vec<bool>>(),
+        bits.iter().by_vals().collect::<Vec<bool>>()
+    );
+    let decoded: Vec<i64> = fib_decode_bitslice(&bits).map(|n| n.unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_other_encodings() {
+    let input: Vec<u8> = vec![0, 1, u8::MAX];
+    let mut bits: BitVec<u16, Msb0> = BitVec::new();
+    input.clone().fib_encode_with_zero_into(&mut bits).unwrap();
+    let decoded: Vec<u8> = fibonacci_codec::fib_decode_u8_with_zero(bits.iter().by_vals())
+        .map(|n| n.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<i16> = vec![-4, 1, i16::MIN];
+    let mut bits: BitVec = BitVec::new();
+    input.clone().negafib_encode_into(&mut bits).unwrap();
+    let decoded: Vec<i16> = fibonacci_codec::negafib_decode_i16(bits.iter().by_vals())
+        .map(|n| n.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_appends_and_subslices() {
+    let mut bits: BitVec<u8, Msb0> = bitvec![u8, Msb0; 1, 0, 1];
+    14_u32.fib_encode_into(&mut bits).unwrap();
+    vec![65_u32, 1].fib_encode_into(&mut bits).unwrap();
+    let decoded: Vec<u32> = fib_decode_bitslice(&bits[3..])
+        .map(|n| n.unwrap())
+        .collect();
+    assert_eq!(vec![14, 65, 1], decoded);
+
+    // The bits preceding the code words don't form a complete one:
+    let decoded: Vec<Result<u32, DecodeError>> = fib_decode_bitslice(&bits[..3]).collect();
+    assert_eq!(
+        vec![Err(DecodeError::Truncated {
+            bit_pos: 0,
+            bits_pending: 3
+        })],
+        decoded
+    );
+}
+
+#[test]
+fn test_error_keeps_previous_bits() {
+    let mut bits: BitVec<u64, Lsb0> = BitVec::new();
+    assert_eq!(
+        Err(ElementEncodeError {
+            index: 1,
+            error: EncodeError::ValueTooSmall(0)
+        }),
+        vec![1_u32, 0, 2].fib_encode_into(&mut bits)
+    );
+    assert_eq!(bits![1, 1], bits.as_bitslice());
+}
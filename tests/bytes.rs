@@ -0,0 +1,313 @@
+use bit_vec::BitVec;
+use fibonacci_codec::{
+    fib_decode_bytes, fib_decode_bytes_with_order, fib_decode_u64, fib_decode_u8_with_zero,
+    negafib_decode_i16, BitOrder, BitSink, ByteBits, ByteSink, DecodeError, ElementEncodeError,
+    Encode, EncodeError, EncodeOne, EncodeOneWithZero, EncodeWithZero, NegafibEncode,
+    NegafibEncodeOne,
+};
+use std::num::NonZeroU16;
+
+#[test]
+fn test_byte_bits_order() {
+    let bytes = [0b1000_0001_u8, 0b0100_0000];
+    let bits: Vec<bool> = ByteBits::new(&bytes[..]).collect();
+    assert_eq!(
+        BitVec::from_bytes(&bytes).iter().collect::<Vec<bool>>(),
+        bits
+    );
+    assert_eq!(16, ByteBits::new(&bytes[..]).len());
+}
+
+#[test]
+fn test_roundtrip_bytes() {
+    let input: Vec<u64> = vec![1, 2, 14, 65, u64::MAX, 3];
+    let bytes = input.clone().fib_encode().unwrap().to_bytes();
+    let decoded: Vec<u64> = fib_decode_bytes(&bytes).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    // Owned byte vectors work too:
+    let decoded: Vec<u64> = fib_decode_bytes(bytes).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<i128> = vec![0, -1, i128::MIN, i128::MAX];
+    let bytes = input.clone().fib_encode().unwrap().to_bytes();
+    let decoded: Vec<i128> = fib_decode_bytes(&bytes[..]).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_same_as_bitvec() {
+    let input: Vec<u64> = vec![256, 3, 1];
+    let bits = input.fib_encode().unwrap();
+    let bytes = bits.to_bytes();
+    let from_bits: Vec<Result<u8, DecodeError>> = fibonacci_codec::fib_decode(&bits).collect();
+    let from_bytes: Vec<Result<u8, DecodeError>> = fib_decode_bytes(&bytes).collect();
+    assert_eq!(from_bits, from_bytes);
+}
+
+#[test]
+fn test_other_decoders() {
+    let input: Vec<u8> = vec![0, 1, u8::MAX];
+    let bytes = input.clone().fib_encode_with_zero().unwrap().to_bytes();
+    let decoded: Vec<u8> = fib_decode_u8_with_zero(ByteBits::new(&bytes))
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<i16> = vec![-4, 1, i16::MIN];
+    let bytes = input.clone().negafib_encode().unwrap().to_bytes();
+    let decoded: Vec<i16> = negafib_decode_i16(ByteBits::new(&bytes))
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_empty_and_padding() {
+    assert_eq!(0, fib_decode_bytes::<u64, _>(Vec::new()).count());
+    let bytes = vec![1_u64, 2].fib_encode().unwrap().to_bytes();
+    assert_eq!(2, fib_decode_bytes::<u64, _>(&bytes).count());
+    // More zero bits than a byte's worth of padding:
+    let decoded: Vec<Result<u64, DecodeError>> = fib_decode_bytes(vec![0_u8, 0]).collect();
+    assert_eq!(
+        vec![Err(DecodeError::Truncated {
+            bit_pos: 7,
+            bits_pending: 16
+        })],
+        decoded
+    );
+    // A code word cut off at the end of the input:
+    let bytes = vec![4000_u64].fib_encode().unwrap().to_bytes();
+    let decoded: Vec<Result<u64, DecodeError>> =
+        fib_decode_u64(ByteBits::new(&bytes[..1])).collect();
+    assert_eq!(
+        vec![Err(DecodeError::Truncated {
+            bit_pos: 3,
+            bits_pending: 8
+        })],
+        decoded
+    );
+}
+
+#[test]
+fn test_encode_bytes_same_as_to_bytes() {
+    let input: Vec<u64> = vec![1, 2, 14, 65, u64::MAX, 3];
+    assert_eq!(
+        input.clone().fib_encode().unwrap().to_bytes(),
+        input.fib_encode_bytes().unwrap()
+    );
+    assert_eq!(
+        4000_u16.fib_encode().unwrap().to_bytes(),
+        4000_u16.fib_encode_bytes().unwrap()
+    );
+
+    let input: Vec<i32> = vec![0, -1, i32::MIN, i32::MAX];
+    assert_eq!(
+        input.clone().fib_encode().unwrap().to_bytes(),
+        input.fib_encode_bytes().unwrap()
+    );
+    let input: Vec<i32> = vec![1, -4, i32::MIN];
+    assert_eq!(
+        input.clone().negafib_encode().unwrap().to_bytes(),
+        input.negafib_encode_bytes().unwrap()
+    );
+    let input: Vec<u8> = vec![0, 1, u8::MAX];
+    assert_eq!(
+        input.clone().fib_encode_with_zero().unwrap().to_bytes(),
+        input.fib_encode_with_zero_bytes().unwrap()
+    );
+    let input = [NonZeroU16::MIN, NonZeroU16::MAX];
+    let (Ok(bits), Ok(bytes)) = (input.fib_encode(), input.fib_encode_bytes());
+    assert_eq!(bits.to_bytes(), bytes);
+}
+
+#[test]
+fn test_byte_sink_appends() {
+    let mut sink = ByteSink::from(vec![0xff]);
+    1_u8.fib_encode_into(&mut sink).unwrap();
+    assert_eq!(10, sink.len());
+    vec![2_u8, 3].fib_encode_into(&mut sink).unwrap();
+    assert_eq!(17, sink.len());
+    assert_eq!(&[0xff, 0b1101_1001, 0b1000_0000], sink.as_bytes());
+}
+
+#[test]
+fn test_byte_sink_push_bits() {
+    /// Appends bits via `BitSink`'s default `push_bits`.
+    struct Bits(BitVec);
+
+    impl BitSink for Bits {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+        fn push_zeros(&mut self, n: usize) {
+            self.0.grow(n, false);
+        }
+        fn set_bit(&mut self, i: usize) {
+            self.0.set(i, true);
+        }
+        fn truncate(&mut self, len: usize) {
+            self.0.truncate(len);
+        }
+    }
+
+    for order in [BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let mut sink = ByteSink::with_bit_order(order);
+        let mut bits = Bits(BitVec::new());
+        for (word, n) in [
+            (0b1011, 4),
+            (0b110, 3),
+            (u64::MAX, 64),
+            (0xff, 0),
+            (0x1234_5678, 20),
+            (0b11, 2),
+            (0, 9),
+        ] {
+            sink.push_bits(word, n);
+            bits.push_bits(word, n);
+            assert_eq!(bits.0.len(), BitSink::len(&sink));
+        }
+        let mut bytes = bits.0.to_bytes();
+        if let BitOrder::LsbFirst = order {
+            bytes.iter_mut().for_each(|b| *b = b.reverse_bits());
+        }
+        assert_eq!(bytes, sink.into_bytes(), "{:?}", order);
+    }
+}
+
+#[test]
+fn test_byte_sink_error_keeps_previous_bits() {
+    let mut sink = ByteSink::new();
+    assert_eq!(
+        Err(ElementEncodeError {
+            index: 1,
+            error: EncodeError::ValueTooSmall(0)
+        }),
+        vec![1_u32, 0, 2].fib_encode_into(&mut sink)
+    );
+    assert_eq!(2, BitSink::len(&sink));
+    assert_eq!(vec![0b1100_0000], sink.into_bytes());
+}
+
+#[test]
+fn test_bit_order_known_vectors() {
+    // code words: "11" (1), "001001011" (50), "000010010000100011" (3003)
+    let input: Vec<u16> = vec![1, 50, 3003];
+    let msb_first = [0b1100_1001, 0b0110_0001, 0b0010_0001, 0b0001_1000];
+    let lsb_first = [0b1001_0011, 0b1000_0110, 0b1000_0100, 0b0001_1000];
+    assert_eq!(
+        &msb_first[..],
+        &input
+            .clone()
+            .fib_encode_bytes_with_order(BitOrder::MsbFirst)
+            .unwrap()[..]
+    );
+    assert_eq!(
+        &lsb_first[..],
+        &input
+            .clone()
+            .fib_encode_bytes_with_order(BitOrder::LsbFirst)
+            .unwrap()[..]
+    );
+    assert_eq!(
+        vec![0b0000_0011],
+        1_u8.fib_encode_bytes_with_order(BitOrder::LsbFirst)
+            .unwrap()
+    );
+
+    let decoded: Vec<u16> = fib_decode_bytes_with_order(&msb_first, BitOrder::MsbFirst)
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+    let decoded: Vec<u16> = fib_decode_bytes_with_order(&lsb_first, BitOrder::LsbFirst)
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_bit_order_roundtrip() {
+    for &order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let input: Vec<u64> = vec![1, 2, 14, 65, u64::MAX, 3];
+        let bytes = input.clone().fib_encode_bytes_with_order(order).unwrap();
+        let decoded: Vec<u64> = fib_decode_bytes_with_order(&bytes, order)
+            .map(|x| x.unwrap())
+            .collect();
+        assert_eq!(input, decoded);
+
+        // The other encodings, via a sink:
+        let input: Vec<i32> = vec![1, -4, i32::MIN];
+        let mut sink = ByteSink::with_bit_order(order);
+        input.clone().negafib_encode_into(&mut sink).unwrap();
+        assert_eq!(order, sink.bit_order());
+        let decoded: Vec<i32> =
+            fibonacci_codec::negafib_decode_i32(ByteBits::with_bit_order(sink.as_bytes(), order))
+                .map(|x| x.unwrap())
+                .collect();
+        assert_eq!(input, decoded);
+    }
+}
+
+#[test]
+fn test_other_encodings_with_order() {
+    for &order in &[BitOrder::MsbFirst, BitOrder::LsbFirst] {
+        let input: Vec<u8> = vec![0, 7, u8::MAX];
+        let bytes = input
+            .clone()
+            .fib_encode_with_zero_bytes_with_order(order)
+            .unwrap();
+        let decoded: Vec<u8> = fib_decode_u8_with_zero(ByteBits::with_bit_order(&bytes, order))
+            .map(|x| x.unwrap())
+            .collect();
+        assert_eq!(input, decoded);
+        let mut sink = ByteSink::with_bit_order(order);
+        input.clone().fib_encode_with_zero_into(&mut sink).unwrap();
+        assert_eq!(sink.into_bytes(), bytes);
+
+        let input: Vec<i16> = vec![1, -4, i16::MIN];
+        let bytes = input
+            .clone()
+            .negafib_encode_bytes_with_order(order)
+            .unwrap();
+        let decoded: Vec<i16> = negafib_decode_i16(ByteBits::with_bit_order(&bytes, order))
+            .map(|x| x.unwrap())
+            .collect();
+        assert_eq!(input, decoded);
+        assert_eq!(
+            (-4_i16).negafib_encode_bytes_with_order(order).unwrap(),
+            vec![-4_i16].negafib_encode_bytes_with_order(order).unwrap()
+        );
+        assert_eq!(
+            7_u8.fib_encode_with_zero_bytes_with_order(order).unwrap(),
+            vec![7_u8]
+                .fib_encode_with_zero_bytes_with_order(order)
+                .unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_byte_sink_from_bytes_with_order() {
+    let mut sink = ByteSink::from_bytes_with_order(vec![0xff], BitOrder::LsbFirst);
+    assert_eq!(BitOrder::LsbFirst, sink.bit_order());
+    // "011" (2) after the first byte, least significant bit first:
+    2_u8.fib_encode_into(&mut sink).unwrap();
+    assert_eq!(11, sink.len());
+    assert_eq!(vec![0xff, 0b0000_0110], sink.into_bytes());
+}
+
+#[test]
+fn test_bit_order_truncate_keeps_padding_zero() {
+    let mut sink = ByteSink::with_bit_order(BitOrder::LsbFirst);
+    // "0011" (3) is encoded, then 0 fails after the first one:
+    assert!(vec![3_u8, 0].fib_encode_into(&mut sink).is_err());
+    3_u8.fib_encode_into(&mut sink).unwrap();
+    assert_eq!(vec![0b1100_1100], sink.into_bytes());
+
+    let mut sink = ByteSink::with_bit_order(BitOrder::LsbFirst);
+    // "011" (2), cut down to its first two bits:
+    2_u8.fib_encode_into(&mut sink).unwrap();
+    assert_eq!(&[0b0000_0110], sink.as_bytes());
+    BitSink::truncate(&mut sink, 2);
+    assert_eq!(vec![0b0000_0010], sink.into_bytes());
+}
@@ -0,0 +1,71 @@
+//! A newtype integer that computes its code words from its fibonacci
+//! table, shared by the tests and the benchmarks.
+
+use fibonacci_codec::{fibonacci_table, FibonacciInteger};
+use num::{CheckedAdd, CheckedSub, One, Zero};
+use std::ops::{Add, Mul, Sub};
+use std::sync::OnceLock;
+
+/// A 48-bit unsigned integer, stored in a u64.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct U48(pub u64);
+
+pub const U48_MAX: u64 = (1 << 48) - 1;
+
+impl Add for U48 {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        U48(self.0 + other.0)
+    }
+}
+
+impl Sub for U48 {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        U48(self.0 - other.0)
+    }
+}
+
+impl Mul for U48 {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        U48(self.0 * other.0)
+    }
+}
+
+impl CheckedAdd for U48 {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        match self.0 + other.0 {
+            n if n > U48_MAX => None,
+            n => Some(U48(n)),
+        }
+    }
+}
+
+impl CheckedSub for U48 {
+    fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(U48)
+    }
+}
+
+impl Zero for U48 {
+    fn zero() -> Self {
+        U48(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl One for U48 {
+    fn one() -> Self {
+        U48(1)
+    }
+}
+
+impl FibonacciInteger for U48 {
+    fn fibonacci_table() -> &'static [Self] {
+        static TABLE: OnceLock<Vec<U48>> = OnceLock::new();
+        TABLE.get_or_init(fibonacci_table)
+    }
+}
@@ -1,6 +1,15 @@
-// use bit_vec::BitVec;
-use fibonacci_codec::{fib_decode_u64, fib_decode_u8, DecodeError, Encode};
+use bit_vec::BitVec;
+use fibonacci_codec::{fib_decode, Decode};
+use fibonacci_codec::{
+    fib_decode_i128, fib_decode_i64, fib_decode_i8, fib_decode_u128, fib_decode_u64, fib_decode_u8,
+    negafib_decode_i128, negafib_decode_i16, negafib_decode_i8, DecodeError, Encode, NegafibEncode,
+};
+use fibonacci_codec::{fib_decode_nonzero_u32, fib_decode_nonzero_u8};
+use fibonacci_codec::{
+    fib_decode_u128_with_zero, fib_decode_u64_with_zero, fib_decode_u8_with_zero, EncodeWithZero,
+};
 use std::iter::Iterator;
+use std::num::{NonZeroU32, NonZeroU8};
 
 #[test]
 fn test_roundtrip_u64() {
@@ -39,3 +48,266 @@ fn test_overflow_from_addition() {
         decoded[0]
     );
 }
+
+#[test]
+fn test_roundtrip_u128() {
+    let input: Vec<u128> = vec![
+        1,
+        u64::MAX as u128 + 1,
+        u128::MAX - 1,
+        u128::MAX,
+        332825110087067562321196029789634457848,
+    ];
+    let encoded = input.clone().fib_encode().unwrap();
+    let decoded: Vec<u128> = fib_decode_u128(encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_overflow_from_u128_addition() {
+    let input: Vec<u128> = vec![u64::MAX as u128 + 1];
+    let encoded = input.fib_encode().unwrap();
+    let decoded: Vec<Result<u64, DecodeError>> = fib_decode_u64(encoded).collect();
+    assert_eq!(
+        Err(DecodeError::ConstructionOverflow { bit_pos: 91 }),
+        decoded[0]
+    );
+}
+
+#[test]
+fn test_overflow_from_u128_fib_elt() {
+    // The u128 table has 185 elements, so a set bit at position 185
+    // can not be represented:
+    let mut encoded = BitVec::from_elem(185, false);
+    encoded.push(true);
+    encoded.push(true);
+    let decoded: Vec<Result<u128, DecodeError>> = fib_decode_u128(encoded).collect();
+    assert_eq!(
+        Err(DecodeError::FibonacciElementOverflow { bit_pos: 185 }),
+        decoded[0]
+    );
+}
+
+#[test]
+fn test_roundtrip_signed() {
+    let input: Vec<i8> = vec![0, -1, 1, i8::MIN, i8::MAX, -100, 100];
+    let encoded = input.clone().fib_encode().unwrap();
+    let decoded: Vec<i8> = fib_decode_i8(encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<i64> = vec![0, -1, 1, i64::MIN, i64::MAX, -23894089128374];
+    let encoded = input.clone().fib_encode().unwrap();
+    let decoded: Vec<i64> = fib_decode_i64(encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<i128> = vec![0, -1, 1, i128::MIN, i128::MAX];
+    let encoded = input.clone().fib_encode().unwrap();
+    let decoded: Vec<i128> = fib_decode_i128(encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_signed_overflow() {
+    // 257 is one more than an i8 can zigzag-map to:
+    let input: Vec<u64> = vec![257, 1];
+    let encoded = input.fib_encode().unwrap();
+    let decoded: Vec<Result<i8, DecodeError>> = fib_decode_i8(encoded).collect();
+    assert_eq!(
+        vec![
+            Err(DecodeError::ConstructionOverflow { bit_pos: 11 }),
+            Ok(0)
+        ],
+        decoded
+    );
+}
+
+#[test]
+fn test_roundtrip_negafib() {
+    let input: Vec<i8> = vec![1, -1, i8::MIN, i8::MAX, -100, 100];
+    let encoded = input.clone().negafib_encode().unwrap();
+    let decoded: Vec<i8> = negafib_decode_i8(encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<i128> = vec![1, -1, i128::MIN, i128::MAX, i128::MIN + 1];
+    let encoded = input.clone().negafib_encode().unwrap();
+    let decoded: Vec<i128> = negafib_decode_i128(encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_negafib_overflow() {
+    let input: Vec<i16> = vec![128, -129, 1];
+    let encoded = input.negafib_encode().unwrap();
+    let decoded: Vec<Result<i8, DecodeError>> = negafib_decode_i8(encoded).collect();
+    assert_eq!(
+        vec![
+            Err(DecodeError::ConstructionOverflow { bit_pos: 10 }),
+            Err(DecodeError::ConstructionOverflow { bit_pos: 11 }),
+            Ok(1)
+        ],
+        decoded
+    );
+
+    let input: Vec<i16> = vec![-300, 1];
+    let encoded = input.clone().negafib_encode().unwrap();
+    let decoded: Vec<Result<i8, DecodeError>> = negafib_decode_i8(&encoded).collect();
+    assert_eq!(
+        vec![
+            Err(DecodeError::FibonacciElementOverflow { bit_pos: 13 }),
+            Ok(1)
+        ],
+        decoded
+    );
+    let decoded: Vec<i16> = negafib_decode_i16(&encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_roundtrip_with_zero() {
+    let input: Vec<u8> = vec![0, 1, 2, u8::MAX - 1, u8::MAX];
+    let encoded = input.clone().fib_encode_with_zero().unwrap();
+    let decoded: Vec<u8> = fib_decode_u8_with_zero(encoded)
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<u64> = vec![0, 1, 2, u64::MAX - 1, u64::MAX];
+    let encoded = input.clone().fib_encode_with_zero().unwrap();
+    let decoded: Vec<u64> = fib_decode_u64_with_zero(encoded)
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<u128> = vec![0, 1, 2, u128::MAX - 1, u128::MAX];
+    let encoded = input.clone().fib_encode_with_zero().unwrap();
+    let decoded: Vec<u128> = fib_decode_u128_with_zero(encoded)
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_overflow_with_zero() {
+    // 257 decodes to 256, which is one more than a u8 can hold:
+    let input: Vec<u64> = vec![257, 256];
+    let encoded = input.fib_encode().unwrap();
+    let decoded: Vec<Result<u8, DecodeError>> = fib_decode_u8_with_zero(encoded).collect();
+    assert_eq!(
+        vec![
+            Err(DecodeError::ConstructionOverflow { bit_pos: 11 }),
+            Ok(u8::MAX)
+        ],
+        decoded
+    );
+}
+
+#[test]
+fn test_roundtrip_nonzero() {
+    let input: Vec<NonZeroU32> = [1, 14, u32::MAX]
+        .iter()
+        .map(|n| NonZeroU32::new(*n).unwrap())
+        .collect();
+    let Ok(encoded) = input.clone().fib_encode();
+    let decoded: Vec<NonZeroU32> = fib_decode_nonzero_u32(encoded)
+        .map(|x| x.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_overflow_nonzero() {
+    let input: Vec<u64> = vec![256, 255];
+    let encoded = input.fib_encode().unwrap();
+    let decoded: Vec<Result<NonZeroU8, DecodeError>> = fib_decode_nonzero_u8(encoded).collect();
+    assert_eq!(
+        vec![
+            Err(DecodeError::ConstructionOverflow { bit_pos: 11 }),
+            Ok(NonZeroU8::new(255).unwrap())
+        ],
+        decoded
+    );
+}
+
+fn roundtrip_generic<T>(input: Vec<T>) -> Vec<T>
+where
+    T: Decode + std::fmt::Debug + Send + Sync,
+    Vec<T>: Encode<T>,
+    <Vec<T> as Encode<T>>::Error: std::fmt::Debug,
+{
+    let encoded = input.fib_encode().unwrap();
+    fib_decode::<T, _>(&encoded).map(|x| x.unwrap()).collect()
+}
+
+#[test]
+fn test_roundtrip_generic() {
+    assert_eq!(vec![2_u8, 14, 65], roundtrip_generic(vec![2_u8, 14, 65]));
+    assert_eq!(vec![1, u128::MAX], roundtrip_generic(vec![1, u128::MAX]));
+    assert_eq!(
+        vec![0, -1, i32::MIN],
+        roundtrip_generic(vec![0, -1, i32::MIN])
+    );
+    let nonzero = vec![NonZeroU32::new(1).unwrap(), NonZeroU32::MAX];
+    assert_eq!(nonzero.clone(), roundtrip_generic(nonzero));
+}
+
+#[test]
+fn test_generic_same_as_per_type() {
+    let encoded = vec![256_u64, 3, 1].fib_encode().unwrap();
+    let generic: Vec<Result<u8, DecodeError>> = fib_decode(&encoded).collect();
+    let per_type: Vec<Result<u8, DecodeError>> = fib_decode_u8(&encoded).collect();
+    assert_eq!(per_type, generic);
+}
+
+#[test]
+fn test_truncated() {
+    let mut encoded = vec![14_u32, 65].fib_encode().unwrap();
+    // Cut the terminator of 65 off:
+    encoded.truncate(encoded.len() - 1);
+    let decoded: Vec<Result<u32, DecodeError>> = fib_decode(&encoded).collect();
+    assert_eq!(
+        vec![
+            Ok(14),
+            Err(DecodeError::Truncated {
+                bit_pos: 1,
+                bits_pending: 9
+            })
+        ],
+        decoded
+    );
+
+    // 3 is encoded as "1011":
+    let mut encoded = vec![-4_i8, 3].negafib_encode().unwrap();
+    encoded.truncate(encoded.len() - 2);
+    let decoded: Vec<Result<i8, DecodeError>> = negafib_decode_i8(&encoded).collect();
+    assert_eq!(
+        vec![
+            Ok(-4),
+            Err(DecodeError::Truncated {
+                bit_pos: 0,
+                bits_pending: 2
+            })
+        ],
+        decoded
+    );
+}
+
+#[test]
+fn test_padding_is_not_truncation() {
+    let mut encoded = vec![14_u32].fib_encode().unwrap();
+    encoded.grow(7, false);
+    let decoded: Vec<Result<u32, DecodeError>> = fib_decode(&encoded).collect();
+    assert_eq!(vec![Ok(14)], decoded);
+
+    encoded.push(false);
+    let decoded: Vec<Result<u32, DecodeError>> = fib_decode(&encoded).collect();
+    assert_eq!(
+        vec![
+            Ok(14),
+            Err(DecodeError::Truncated {
+                bit_pos: 7,
+                bits_pending: 8
+            })
+        ],
+        decoded
+    );
+}
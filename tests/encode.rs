@@ -1,11 +1,16 @@
 use bit_vec::BitVec;
-use fibonacci_codec::{Encode, EncodeOne};
+use fibonacci_codec::{
+    ElementEncodeError, Encode, EncodeError, EncodeOne, EncodeOneWithZero, EncodeWithZero,
+    NegafibEncode, NegafibEncodeOne,
+};
+use std::num::{NonZeroU128, NonZeroU32, NonZeroU8};
 
 fn to_bits(slice: BitVec) -> Vec<u8> {
     slice.iter().map(|b| if b { 1 } else { 0 } as u8).collect()
 }
 
 #[test]
+#[allow(clippy::unnecessary_cast)]
 fn test_factor_wikipedia() {
     // Some of the examples on https://en.wikipedia.org/wiki/Fibonacci_coding:
     assert_eq!(vec![1, 1], to_bits((1 as u8).fib_encode().unwrap()));
@@ -42,6 +47,9 @@ fn check_slice_u32(numbers: Vec<u32>) -> Vec<u8> {
 fn check_slice_u64(numbers: Vec<u64>) -> Vec<u8> {
     to_bits(numbers.fib_encode().unwrap())
 }
+fn check_slice_u128(numbers: Vec<u128>) -> Vec<u8> {
+    to_bits(numbers.fib_encode().unwrap())
+}
 
 #[test]
 fn test_slices_singles() {
@@ -49,6 +57,7 @@ fn test_slices_singles() {
     assert_eq!(vec![0, 1, 1], check_slice_u16(vec![2]));
     assert_eq!(vec![0, 1, 1], check_slice_u32(vec![2]));
     assert_eq!(vec![0, 1, 1], check_slice_u64(vec![2]));
+    assert_eq!(vec![0, 1, 1], check_slice_u128(vec![2]));
 }
 
 #[test]
@@ -89,4 +98,190 @@ fn test_slices_multi() {
         ],
         check_slice_u64(vec![2, 14, 65])
     );
+    assert_eq!(vec![1, 1, 1, 1], check_slice_u128(vec![1, 1]));
+    assert_eq!(
+        vec![
+            0, 1, 1, // 1
+            1, 0, 0, 0, 0, 1, 1, // 14
+            0, 1, 0, 0, 1, 0, 0, 0, 1, 1, // 65
+        ],
+        check_slice_u128(vec![2, 14, 65])
+    );
+}
+
+#[test]
+fn test_u128_max() {
+    let bits = to_bits(u128::MAX.fib_encode().unwrap());
+    // The largest fibonacci number that fits into a u128 is the
+    // 185th element of the table, so u128::MAX needs 186 bits:
+    assert_eq!(186, bits.len());
+    assert_eq!(vec![1, 0, 1, 0, 0, 0, 1], bits[..7].to_vec());
+    assert_eq!(vec![0, 0, 0, 1, 1], bits[181..].to_vec());
+}
+
+#[test]
+fn test_signed_zigzag() {
+    assert_eq!(vec![1, 1], to_bits(0_i8.fib_encode().unwrap()));
+    assert_eq!(vec![0, 1, 1], to_bits((-1_i16).fib_encode().unwrap()));
+    assert_eq!(vec![0, 0, 1, 1], to_bits(1_i32.fib_encode().unwrap()));
+    assert_eq!(vec![1, 0, 1, 1], to_bits((-2_i64).fib_encode().unwrap()));
+    assert_eq!(
+        vec![1, 1, 0, 1, 1],
+        vec![0_i128, -1].fib_encode().map(to_bits).unwrap()
+    );
+    // i8::MAX is mapped to 254, and encoded as 255 = 233 + 21 + 1:
+    assert_eq!(
+        vec![1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1],
+        to_bits(i8::MAX.fib_encode().unwrap())
+    );
+    // i8::MIN is mapped to 255, and encoded as 256 = 233 + 21 + 2:
+    assert_eq!(
+        vec![0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1],
+        to_bits(i8::MIN.fib_encode().unwrap())
+    );
+}
+
+#[test]
+fn test_negafib_wikipedia() {
+    // The examples on https://en.wikipedia.org/wiki/Negafibonacci_coding:
+    assert_eq!(vec![1, 1], to_bits(1_i8.negafib_encode().unwrap()));
+    assert_eq!(vec![0, 1, 1], to_bits((-1_i8).negafib_encode().unwrap()));
+    assert_eq!(vec![0, 0, 1, 1], to_bits(2_i16.negafib_encode().unwrap()));
+    assert_eq!(
+        vec![1, 0, 0, 1, 1],
+        to_bits((-2_i16).negafib_encode().unwrap())
+    );
+    assert_eq!(vec![1, 0, 1, 1], to_bits(3_i32.negafib_encode().unwrap()));
+    assert_eq!(
+        vec![0, 0, 0, 1, 1],
+        to_bits((-3_i32).negafib_encode().unwrap())
+    );
+    assert_eq!(
+        vec![0, 1, 0, 0, 1, 1],
+        to_bits(4_i64.negafib_encode().unwrap())
+    );
+    assert_eq!(
+        vec![0, 1, 0, 1, 1],
+        to_bits((-4_i64).negafib_encode().unwrap())
+    );
+    assert_eq!(
+        vec![0, 0, 0, 0, 1, 1],
+        to_bits(5_i128.negafib_encode().unwrap())
+    );
+    assert_eq!(
+        vec![1, 0, 1, 0, 0, 1, 1],
+        to_bits((-5_i128).negafib_encode().unwrap())
+    );
+}
+
+#[test]
+fn test_negafib_extremes() {
+    // -128 = 1 + 2 + 13 - 144:
+    assert_eq!(
+        vec![1, 0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1],
+        to_bits(i8::MIN.negafib_encode().unwrap())
+    );
+    // 127 = -1 + 5 + 34 + 89:
+    assert_eq!(
+        vec![0, 1, 0, 0, 1, 0, 0, 0, 1, 0, 1, 1],
+        to_bits(i8::MAX.negafib_encode().unwrap())
+    );
+}
+
+#[test]
+fn test_negafib_zero() {
+    assert_eq!(Err(EncodeError::ValueTooSmall(0)), 0_i32.negafib_encode());
+    let err = vec![1_i32, 0].negafib_encode().unwrap_err();
+    assert_eq!(1, err.index);
+    assert_eq!(EncodeError::ValueTooSmall(0), err.error);
+}
+
+#[test]
+fn test_with_zero() {
+    assert_eq!(vec![1, 1], to_bits(0_u8.fib_encode_with_zero().unwrap()));
+    assert_eq!(
+        vec![0, 1, 1],
+        to_bits(1_u16.fib_encode_with_zero().unwrap())
+    );
+    assert_eq!(
+        vec![1, 0, 0, 0, 0, 1, 1],
+        to_bits(13_u32.fib_encode_with_zero().unwrap())
+    );
+    assert_eq!(
+        vec![1, 1, 0, 1, 1],
+        to_bits(vec![0_u64, 1].fib_encode_with_zero().unwrap())
+    );
+    // u8::MAX is encoded as 256 = 233 + 21 + 2:
+    assert_eq!(
+        vec![0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 1],
+        to_bits(u8::MAX.fib_encode_with_zero().unwrap())
+    );
+    assert_eq!(
+        to_bits((u64::MAX as u128 + 1).fib_encode().unwrap()),
+        to_bits(u64::MAX.fib_encode_with_zero().unwrap())
+    );
+}
+
+#[test]
+fn test_nonzero() {
+    let Ok(bits) = NonZeroU8::new(14).unwrap().fib_encode();
+    assert_eq!(vec![1, 0, 0, 0, 0, 1, 1], to_bits(bits));
+    let Ok(bits) = NonZeroU128::new(u128::MAX).unwrap().fib_encode();
+    assert_eq!(to_bits(u128::MAX.fib_encode().unwrap()), to_bits(bits));
+
+    let numbers: Vec<NonZeroU32> = [2, 14, 65]
+        .iter()
+        .map(|n| NonZeroU32::new(*n).unwrap())
+        .collect();
+    let Ok(bits) = numbers.fib_encode();
+    assert_eq!(
+        vec![
+            0, 1, 1, // 1
+            1, 0, 0, 0, 0, 1, 1, // 14
+            0, 1, 0, 0, 1, 0, 0, 0, 1, 1, // 65
+        ],
+        to_bits(bits)
+    );
+}
+
+#[test]
+fn test_borrowed() {
+    let numbers: Vec<u32> = vec![2, 14, 65];
+    let owned = numbers.clone().fib_encode().unwrap();
+    assert_eq!(owned, (&numbers).fib_encode().unwrap());
+    assert_eq!(owned, numbers[..].iter().fib_encode().unwrap());
+    let slice: &[u32] = &numbers;
+    assert_eq!(owned, slice.fib_encode().unwrap());
+
+    let numbers: Vec<i16> = vec![0, -1, i16::MIN];
+    assert_eq!(
+        numbers.clone().fib_encode().unwrap(),
+        numbers.iter().fib_encode().unwrap()
+    );
+    let numbers: Vec<i16> = vec![1, -1, i16::MIN];
+    assert_eq!(
+        numbers.clone().negafib_encode().unwrap(),
+        numbers.iter().negafib_encode().unwrap()
+    );
+    let numbers: Vec<u8> = vec![0, u8::MAX];
+    assert_eq!(
+        numbers.clone().fib_encode_with_zero().unwrap(),
+        numbers.iter().fib_encode_with_zero().unwrap()
+    );
+    let numbers = [NonZeroU8::new(3).unwrap(), NonZeroU8::MAX];
+    let Ok(owned) = numbers.fib_encode();
+    let Ok(borrowed) = numbers.iter().fib_encode();
+    assert_eq!(owned, borrowed);
+}
+
+#[test]
+fn test_borrowed_error_index() {
+    let numbers: &[u64] = &[1, 2, 0, 3];
+    assert_eq!(
+        Err(ElementEncodeError {
+            index: 2,
+            error: EncodeError::ValueTooSmall(0)
+        }),
+        numbers.fib_encode()
+    );
 }
@@ -0,0 +1,168 @@
+use fibonacci_codec::{
+    fib_decode_bytes, fib_decode_bytes_with_order, BitOrder, DecodeError, Encode, FibDecoder,
+};
+use std::convert::TryInto;
+use std::num::NonZeroU16;
+
+fn feed_in_chunks<T>(
+    decoder: &mut FibDecoder<T>,
+    bytes: &[u8],
+    size: usize,
+) -> Vec<Result<T, DecodeError>>
+where
+    T: fibonacci_codec::IncrementalDecode,
+{
+    let mut decoded = vec![];
+    for chunk in bytes.chunks(size) {
+        decoded.extend(decoder.feed(chunk));
+    }
+    decoded
+}
+
+#[test]
+fn test_any_chunk_size() {
+    let input: Vec<u64> = (1..500)
+        .map(|n| n * n * 7919)
+        .chain(vec![u64::MAX])
+        .collect();
+    let bytes = input.clone().fib_encode().unwrap().to_bytes();
+    for size in 1..=bytes.len() {
+        let mut decoder = FibDecoder::<u64>::new();
+        let decoded: Vec<u64> = feed_in_chunks(&mut decoder, &bytes, size)
+            .into_iter()
+            .map(|n| n.unwrap())
+            .collect();
+        assert_eq!(input, decoded, "chunk size {}", size);
+        assert_eq!(Ok(()), decoder.finish());
+    }
+}
+
+#[test]
+fn test_signed_and_nonzero() {
+    let input = vec![0_i32, -1, 1, i32::MIN, i32::MAX, -3003];
+    let bytes = input.clone().fib_encode().unwrap().to_bytes();
+    let mut decoder = FibDecoder::<i32>::default();
+    let decoded: Vec<i32> = feed_in_chunks(&mut decoder, &bytes, 1)
+        .into_iter()
+        .map(|n| n.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+    assert_eq!(Ok(()), decoder.finish());
+
+    let input: Vec<NonZeroU16> = [1, 2, 50, u16::MAX]
+        .iter()
+        .map(|n| NonZeroU16::new(*n).unwrap())
+        .collect();
+    let bytes = input.clone().fib_encode().unwrap().to_bytes();
+    let mut decoder = FibDecoder::<NonZeroU16>::new();
+    let decoded: Vec<NonZeroU16> = feed_in_chunks(&mut decoder, &bytes, 2)
+        .into_iter()
+        .map(|n| n.unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_bit_order() {
+    let input = vec![1_u16, 50, 3003, u16::MAX];
+    let bytes = input
+        .clone()
+        .fib_encode_bytes_with_order(BitOrder::LsbFirst)
+        .unwrap();
+    let mut decoder = FibDecoder::<u16>::with_bit_order(BitOrder::LsbFirst);
+    let decoded = feed_in_chunks(&mut decoder, &bytes, 3);
+    let expected: Vec<Result<u16, DecodeError>> =
+        fib_decode_bytes_with_order(&bytes, BitOrder::LsbFirst).collect();
+    assert_eq!(expected, decoded);
+    assert_eq!(Ok(()), decoder.finish());
+}
+
+#[test]
+fn test_errors_across_chunks() {
+    // 256 overflows a u8, 4000 overflows the fibonacci table:
+    let bytes = vec![256_u64, 3, 4000, 7].fib_encode().unwrap().to_bytes();
+    let expected: Vec<Result<u8, DecodeError>> = fib_decode_bytes(&bytes).collect();
+    for size in 1..=bytes.len() {
+        let mut decoder = FibDecoder::<u8>::new();
+        assert_eq!(expected, feed_in_chunks(&mut decoder, &bytes, size));
+        assert_eq!(Ok(()), decoder.finish());
+    }
+}
+
+#[test]
+fn test_unconsumed_results_are_kept() {
+    let bytes = vec![1_u8, 2, 3, 4, 5, 6].fib_encode().unwrap().to_bytes();
+    let mut decoder = FibDecoder::<u8>::new();
+    // The first byte holds the code words of 1, 2 and part of 3:
+    drop(decoder.feed(&bytes[..1]));
+    let decoded: Vec<u8> = decoder.feed(&bytes[1..]).map(|n| n.unwrap()).collect();
+    assert_eq!(vec![1, 2, 3, 4, 5, 6], decoded);
+    decoder.finish().unwrap();
+
+    // Results that weren't handed out before finishing aren't lost
+    // either:
+    assert_eq!(Some(Ok(1)), decoder.feed(&bytes).next());
+    decoder.finish().unwrap();
+    let decoded: Vec<u8> = decoder.feed(&[]).map(|n| n.unwrap()).collect();
+    assert_eq!(vec![2, 3, 4, 5, 6], decoded);
+}
+
+#[test]
+fn test_finish_reports_dangling_word() {
+    let mut bytes = vec![3_u16, 4000].fib_encode().unwrap().to_bytes();
+    bytes.pop();
+    let mut decoder = FibDecoder::<u16>::new();
+    let decoded = feed_in_chunks(&mut decoder, &bytes, 1);
+    assert_eq!(vec![Ok(3)], decoded);
+    let expected = fib_decode_bytes::<u16, _>(&bytes).nth(1).unwrap();
+    assert!(matches!(expected, Err(DecodeError::Truncated { .. })));
+    assert_eq!(expected.map(|_| ()), decoder.finish());
+    // Finishing resets the decoder:
+    assert_eq!(Ok(()), decoder.finish());
+
+    // The decoder can be reused after finishing:
+    let bytes = vec![7_u16].fib_encode().unwrap().to_bytes();
+    assert_eq!(vec![Ok(7)], feed_in_chunks(&mut decoder, &bytes, 1));
+    assert_eq!(Ok(()), decoder.finish());
+}
+
+#[test]
+fn test_feed_words() {
+    // Encoding 1 as "11" makes for runs of set bits:
+    let input: Vec<u32> = (0..300)
+        .map(|n| if n % 3 == 0 { 1 } else { n * 7919 })
+        .collect();
+    let bits = input.clone().fib_encode().unwrap();
+    let mut words = vec![0_u64; bits.len().div_ceil(64)];
+    for (i, bit) in bits.iter().enumerate() {
+        words[i / 64] |= (bit as u64) << (i % 64);
+    }
+    let mut decoder = FibDecoder::<u32>::with_bit_order(BitOrder::LsbFirst);
+    let mut decoded: Vec<Result<u32, DecodeError>> = decoder.feed_words(&words).collect();
+    if let Err(e) = decoder.finish() {
+        decoded.push(Err(e));
+    }
+    // The padding of the last word is longer than a byte's:
+    let mut bytes = bits.to_bytes();
+    bytes.resize(words.len() * 8, 0);
+    let expected: Vec<Result<u32, DecodeError>> = fib_decode_bytes(&bytes).collect();
+    assert_eq!(expected, decoded);
+    let values: Vec<u32> = decoded.into_iter().filter_map(|n| n.ok()).collect();
+    assert_eq!(input, values);
+
+    // Words and bytes can be mixed; code words carry over between them:
+    let mut decoder = FibDecoder::<u32>::new();
+    let mut decoded = vec![];
+    for (i, word) in bytes.chunks(8).enumerate() {
+        if i % 2 == 0 {
+            let word = u64::from_be_bytes(word.try_into().unwrap());
+            decoded.extend(decoder.feed_words(&[word]));
+        } else {
+            decoded.extend(decoder.feed(word));
+        }
+    }
+    if let Err(e) = decoder.finish() {
+        decoded.push(Err(e));
+    }
+    assert_eq!(expected, decoded);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::{U48, U48_MAX};
+use fibonacci_codec::{
+    fib_decode, fib_decode_u64, fibonacci_table, DecodeError, Encode, EncodeOne, EncodeWithZero,
+    FibonacciInteger,
+};
+
+#[test]
+fn test_table() {
+    let table = U48::fibonacci_table();
+    assert_eq!(&[U48(1), U48(2), U48(3), U48(5)], &table[..4]);
+    assert!(table.last().unwrap().0 <= U48_MAX);
+    assert_eq!(fibonacci_table::<u8>(), u8::fibonacci_table());
+    assert_eq!(fibonacci_table::<u128>(), u128::fibonacci_table());
+}
+
+#[test]
+fn test_same_as_u64() {
+    let numbers: Vec<u64> = vec![1, 2, 14, 65, 3003, U48_MAX];
+    let wrapped: Vec<U48> = numbers.iter().map(|n| U48(*n)).collect();
+    assert_eq!(
+        U48(3003).fib_encode().unwrap(),
+        3003_u64.fib_encode().unwrap()
+    );
+    let encoded = wrapped.clone().fib_encode().unwrap();
+    assert_eq!(numbers.fib_encode().unwrap(), encoded);
+
+    let decoded: Vec<U48> = fib_decode(&encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(wrapped, decoded);
+
+    // U48 computes every code word from its table, while those of u64
+    // values are built a few digits at a time; the digits change the
+    // most around fibonacci numbers and their sums:
+    let table = U48::fibonacci_table();
+    let mut boundaries = vec![];
+    for (i, elt) in table.iter().enumerate() {
+        for lower in [U48(0)].iter().chain(&table[..i.saturating_sub(1)]) {
+            for n in [elt.0 + lower.0 - 1, elt.0 + lower.0, elt.0 + lower.0 + 1] {
+                if n > 0 && n <= U48_MAX {
+                    assert_eq!(
+                        U48(n).fib_encode().unwrap(),
+                        n.fib_encode().unwrap(),
+                        "{}",
+                        n
+                    );
+                    boundaries.push(n);
+                }
+            }
+        }
+    }
+    // Batches may be encoded with other instructions than single
+    // numbers:
+    let wrapped: Vec<U48> = boundaries.iter().map(|n| U48(*n)).collect();
+    assert_eq!(
+        wrapped.fib_encode().unwrap(),
+        boundaries.fib_encode().unwrap()
+    );
+}
+
+#[test]
+fn test_overflow() {
+    let numbers: Vec<u64> = vec![U48_MAX + 1, 1];
+    let encoded = numbers.fib_encode().unwrap();
+    let decoded: Vec<Result<U48, DecodeError>> = fib_decode(&encoded).collect();
+    assert!(decoded[0].is_err());
+    assert_eq!(Ok(U48(1)), decoded[1]);
+
+    let decoded: Vec<u64> = fib_decode_u64(&encoded).map(|x| x.unwrap()).collect();
+    assert_eq!(vec![U48_MAX + 1, 1], decoded);
+}
+
+#[test]
+fn test_same_as_lookup() {
+    // U48 computes every code word from its table, while the primitive
+    // types look up the ones of small values:
+    for n in 0..=u16::MAX {
+        let wrapped = U48(n as u64);
+        if n > 0 {
+            let encoded = wrapped.fib_encode().unwrap();
+            assert_eq!(encoded, n.fib_encode().unwrap(), "{}", n);
+            assert_eq!(encoded, (n as u64).fib_encode().unwrap(), "{}", n);
+            assert_eq!(
+                vec![wrapped].fib_encode_terminated().unwrap(),
+                vec![n as u32].fib_encode_terminated().unwrap(),
+                "{}",
+                n
+            );
+        }
+        assert_eq!(
+            vec![wrapped].fib_encode_with_zero().unwrap(),
+            vec![n].fib_encode_with_zero().unwrap(),
+            "{}",
+            n
+        );
+    }
+}
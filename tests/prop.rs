@@ -1,9 +1,54 @@
-use proptest;
-
-use fibonacci_codec::{fib_decode_u16, fib_decode_u32, fib_decode_u64, fib_decode_u8};
-use fibonacci_codec::{Encode, EncodeOne};
+use fibonacci_codec::{
+    fib_decode_bytes, fib_decode_bytes_with_order, fib_decode_terminated, BitOrder, ByteBits,
+    Decode, DecodeError, Encode, EncodeOne, EncodeOneWithZero, EncodeWithZero, FibDecoder,
+    IncrementalDecode,
+};
+use fibonacci_codec::{
+    fib_decode_i128, fib_decode_i64, fib_decode_u128, fib_decode_u16, fib_decode_u32,
+    fib_decode_u64, fib_decode_u8,
+};
+use fibonacci_codec::{fib_decode_nonzero_u64, fib_decode_u32_with_zero, fib_decode_u64_with_zero};
+use fibonacci_codec::{negafib_decode_i32, negafib_decode_i64};
+use fibonacci_codec::{NegafibEncode, NegafibEncodeOne};
 
 use proptest::prelude::*;
+use std::convert::TryInto;
+use std::fmt::Debug;
+use std::num::{NonZeroU16, NonZeroU64};
+
+/// Checks that a `FibDecoder` fed `bytes` in chunks, or as words, gives
+/// the same results as the bit-at-a-time decoder.
+fn decodes_like_bitwise<T>(bytes: &[u8], chunk: usize, order: BitOrder) -> Result<(), TestCaseError>
+where
+    T: Decode + IncrementalDecode + Debug + PartialEq,
+{
+    let mut decoder = FibDecoder::<T>::with_bit_order(order);
+    let mut decoded = vec![];
+    for chunk in bytes.chunks(chunk) {
+        decoded.extend(decoder.feed(chunk));
+    }
+    if let Err(e) = decoder.finish() {
+        decoded.push(Err(e));
+    }
+    let expected: Vec<Result<T, DecodeError>> = fib_decode_bytes_with_order(bytes, order).collect();
+    prop_assert_eq!(expected, decoded);
+
+    let words: Vec<u64> = bytes
+        .chunks_exact(8)
+        .map(|word| match order {
+            BitOrder::MsbFirst => u64::from_be_bytes(word.try_into().unwrap()),
+            BitOrder::LsbFirst => u64::from_le_bytes(word.try_into().unwrap()),
+        })
+        .collect();
+    let mut decoded: Vec<Result<T, DecodeError>> = decoder.feed_words(&words).collect();
+    if let Err(e) = decoder.finish() {
+        decoded.push(Err(e));
+    }
+    let expected: Vec<Result<T, DecodeError>> =
+        fib_decode_bytes_with_order(&bytes[..words.len() * 8], order).collect();
+    prop_assert_eq!(expected, decoded);
+    Ok(())
+}
 
 proptest! {
    #[test]
@@ -23,7 +68,7 @@ proptest! {
             prop_assert_eq!(decoded, vec![input as u32], "Decoding as u32");
         }
         let decoded: Vec<u64> = fib_decode_u64(&bits).filter_map(|x| x.ok()).collect();
-        prop_assert_eq!(decoded, vec![input as u64], "Decoding as u64");
+        prop_assert_eq!(decoded, vec![input], "Decoding as u64");
     }
 
     #[test]
@@ -35,4 +80,133 @@ proptest! {
         let decoded: Vec<u64> = fib_decode_u64(bits).filter_map(|x| x.ok()).collect();
         prop_assert_eq!(decoded, input.clone());
     }
+
+    #[test]
+    fn roundtrips_u128(input in any::<u128>().prop_filter("Values must be >0".to_owned(),
+                                                          |v| *v > 0)) {
+        let bits = input.fib_encode().expect("Expected an Ok result");
+        let decoded: Vec<u128> = fib_decode_u128(&bits).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, vec![input], "Decoding as u128");
+        if input <= u64::MAX as u128 {
+            let decoded: Vec<u64> = fib_decode_u64(&bits).filter_map(|x| x.ok()).collect();
+            prop_assert_eq!(decoded, vec![input as u64], "Decoding as u64");
+        }
+    }
+
+    #[test]
+    fn roundtrips_signed(input in any::<i64>()) {
+        let bits = input.fib_encode().expect("Expected an Ok result");
+        let decoded: Vec<i64> = fib_decode_i64(&bits).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, vec![input], "Decoding as i64");
+    }
+
+    #[test]
+    fn roundtrips_signed_array(ref input in proptest::collection::vec(any::<i128>(), 1..100)) {
+        let bits = input.clone().fib_encode().expect("Expected an Ok result");
+        let decoded: Vec<i128> = fib_decode_i128(bits).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, input.clone());
+    }
+
+    #[test]
+    fn roundtrips_negafib(input in any::<i64>().prop_filter("Values must not be 0".to_owned(),
+                                                            |v| *v != 0)) {
+        let bits = input.negafib_encode().expect("Expected an Ok result");
+        if input >= i32::MIN as i64 && input <= i32::MAX as i64 {
+            let decoded: Vec<i32> = negafib_decode_i32(&bits).filter_map(|x| x.ok()).collect();
+            prop_assert_eq!(decoded, vec![input as i32], "Decoding as i32");
+        }
+        let decoded: Vec<i64> = negafib_decode_i64(&bits).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, vec![input], "Decoding as i64");
+    }
+
+    #[test]
+    fn roundtrips_negafib_array(ref input in
+                                proptest::collection::vec(any::<i64>().prop_filter("Values must not be 0".to_owned(),
+                                                                                   |v| *v != 0),
+                                                          1..100)) {
+        let bits = input.clone().negafib_encode().expect("Expected an Ok result");
+        let decoded: Vec<i64> = negafib_decode_i64(bits).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, input.clone());
+    }
+
+    #[test]
+    fn roundtrips_with_zero(input in any::<u64>()) {
+        let bits = input.fib_encode_with_zero().expect("Expected an Ok result");
+        if input <= 0xffffffff {
+            let decoded: Vec<u32> = fib_decode_u32_with_zero(&bits).filter_map(|x| x.ok()).collect();
+            prop_assert_eq!(decoded, vec![input as u32], "Decoding as u32");
+        }
+        let decoded: Vec<u64> = fib_decode_u64_with_zero(&bits).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, vec![input], "Decoding as u64");
+    }
+
+    #[test]
+    fn roundtrips_with_zero_array(ref input in proptest::collection::vec(any::<u64>(), 1..100)) {
+        let bits = input.clone().fib_encode_with_zero().expect("Expected an Ok result");
+        let decoded: Vec<u64> = fib_decode_u64_with_zero(bits).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, input.clone());
+    }
+
+    #[test]
+    fn roundtrips_nonzero(input in any::<NonZeroU64>()) {
+        let Ok(bits) = input.fib_encode();
+        let decoded: Vec<NonZeroU64> = fib_decode_nonzero_u64(&bits).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, vec![input], "Decoding as NonZeroU64");
+    }
+
+    #[test]
+    fn decodes_bytes(ref input in proptest::collection::vec(any::<u64>().prop_filter("Values must be >0".to_owned(),
+                                                                                     |v| *v > 0),
+                                                            0..100)) {
+        let bytes = input.clone().fib_encode().expect("Expected an Ok result").to_bytes();
+        let decoded: Vec<u64> = fib_decode_bytes(&bytes).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, input.clone());
+    }
+
+    #[test]
+    fn encodes_bytes(ref input in proptest::collection::vec(any::<i64>(), 0..100)) {
+        let bits = input.clone().fib_encode().expect("Expected an Ok result");
+        let bytes = input.clone().fib_encode_bytes().expect("Expected an Ok result");
+        prop_assert_eq!(bits.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn roundtrips_terminated(ref input in proptest::collection::vec(any::<i32>(), 0..100),
+                             ref rest in proptest::collection::vec(any::<u8>(), 0..4)) {
+        let mut bytes = input.clone().fib_encode_terminated().expect("Expected an Ok result").to_bytes();
+        bytes.extend(rest);
+        let mut decoded = fib_decode_terminated::<i32, _>(ByteBits::new(&bytes));
+        let values: Vec<i32> = decoded.by_ref().filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(values, input.clone());
+        prop_assert!(decoded.is_terminated());
+    }
+
+    #[test]
+    fn roundtrips_lsb_first(ref input in proptest::collection::vec(any::<i64>(), 0..100)) {
+        let bytes = input.clone().fib_encode_bytes_with_order(BitOrder::LsbFirst).expect("Expected an Ok result");
+        let msb_first = input.clone().fib_encode_bytes().expect("Expected an Ok result");
+        let reversed: Vec<u8> = msb_first.iter().map(|b| b.reverse_bits()).collect();
+        prop_assert_eq!(&bytes, &reversed);
+        let decoded: Vec<i64> = fib_decode_bytes_with_order(&bytes, BitOrder::LsbFirst).filter_map(|x| x.ok()).collect();
+        prop_assert_eq!(decoded, input.clone());
+    }
+
+    #[test]
+    fn decodes_bytes_like_bitwise(ref bytes in proptest::collection::vec(
+                                      // Sparse bytes make for long code words, which overflow;
+                                      // runs of set bits for many short ones:
+                                      prop_oneof![any::<u8>(), Just(0_u8), Just(0xff_u8),
+                                                  any::<[u8; 3]>().prop_map(|b| b[0] & b[1] & b[2])],
+                                      0..200),
+                                  chunk in 1..20_usize,
+                                  lsb_first in any::<bool>()) {
+        let order = if lsb_first { BitOrder::LsbFirst } else { BitOrder::MsbFirst };
+        decodes_like_bitwise::<u8>(bytes, chunk, order)?;
+        decodes_like_bitwise::<u16>(bytes, chunk, order)?;
+        decodes_like_bitwise::<u64>(bytes, chunk, order)?;
+        decodes_like_bitwise::<u128>(bytes, chunk, order)?;
+        decodes_like_bitwise::<i8>(bytes, chunk, order)?;
+        decodes_like_bitwise::<i32>(bytes, chunk, order)?;
+        decodes_like_bitwise::<NonZeroU16>(bytes, chunk, order)?;
+    }
 }
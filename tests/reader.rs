@@ -0,0 +1,133 @@
+use fibonacci_codec::{fib_decode_bytes, BitOrder, DecodeError, Encode, FibReader, FibWriter};
+use std::io::{self, Cursor, Read};
+
+#[test]
+fn test_roundtrip_writer() {
+    let input: Vec<u64> = (1..50_000).map(|n| n * 13).collect();
+    let mut writer = FibWriter::new(Vec::new());
+    writer.extend(input.iter().copied());
+    let bytes = writer.finish().unwrap();
+
+    let reader = FibReader::<_, u64>::new(Cursor::new(bytes));
+    let decoded: Vec<u64> = reader.map(|n| n.unwrap().unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_bit_order() {
+    let input: Vec<u32> = vec![1, 50, 3003, u32::MAX];
+    let mut writer = FibWriter::with_bit_order(Vec::new(), BitOrder::LsbFirst);
+    writer.extend(input.iter().copied());
+    let bytes = writer.finish().unwrap();
+    assert_eq!(
+        input
+            .clone()
+            .fib_encode_bytes_with_order(BitOrder::LsbFirst)
+            .unwrap(),
+        bytes
+    );
+
+    let decoded: Vec<u32> = FibReader::with_bit_order(&bytes[..], BitOrder::LsbFirst)
+        .map(|n| n.unwrap().unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_decode_errors() {
+    let bytes = vec![256_u64, 3].fib_encode().unwrap().to_bytes();
+    let decoded: Vec<Result<u8, DecodeError>> =
+        FibReader::new(&bytes[..]).map(|n| n.unwrap()).collect();
+    assert_eq!(
+        vec![
+            Err(DecodeError::ConstructionOverflow { bit_pos: 11 }),
+            Ok(3)
+        ],
+        decoded
+    );
+}
+
+/// Returns its data in chunks of at most `chunk` bytes, failing with
+/// `Interrupted` before each chunk, and once with the kind of `error`
+/// when it reaches the error's offset.
+#[derive(Debug)]
+struct ChunkedReader {
+    data: Vec<u8>,
+    pos: usize,
+    chunk: usize,
+    interrupt: bool,
+    error: Option<(usize, io::ErrorKind)>,
+}
+
+impl Read for ChunkedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+        let mut end = self.data.len();
+        if let Some((offset, kind)) = self.error {
+            if self.pos == offset {
+                self.error = None;
+                return Err(kind.into());
+            }
+            end = end.min(offset);
+        }
+        let n = self.chunk.min(buf.len()).min(end - self.pos);
+        buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[test]
+fn test_chunked_and_interrupted() {
+    let input: Vec<i32> = vec![0, -1, i32::MIN, i32::MAX, 70];
+    let reader = ChunkedReader {
+        data: input.clone().fib_encode().unwrap().to_bytes(),
+        pos: 0,
+        chunk: 1,
+        interrupt: false,
+        error: None,
+    };
+    let decoded: Vec<i32> = FibReader::new(reader)
+        .map(|n| n.unwrap().unwrap())
+        .collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_io_error() {
+    let input: Vec<u32> = vec![1, 2, 3, 3003];
+    let reader = ChunkedReader {
+        data: input.clone().fib_encode().unwrap().to_bytes(),
+        pos: 0,
+        chunk: 1,
+        interrupt: false,
+        // After the first byte, which ends in the first bits of 3:
+        error: Some((1, io::ErrorKind::ConnectionReset)),
+    };
+    let mut reader = FibReader::<_, u32>::new(reader);
+    assert_eq!(1, reader.next().unwrap().unwrap().unwrap());
+    assert_eq!(2, reader.next().unwrap().unwrap().unwrap());
+    assert_eq!(
+        io::ErrorKind::ConnectionReset,
+        reader.next().unwrap().unwrap_err().kind()
+    );
+    // Retrying continues the partial code word:
+    let decoded: Vec<u32> = reader.by_ref().map(|n| n.unwrap().unwrap()).collect();
+    assert_eq!(vec![3, 3003], decoded);
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn test_truncated() {
+    let mut data = vec![1_u32, 2].fib_encode().unwrap().to_bytes();
+    // The start of a code word that never ends:
+    data.push(0b0001_0000);
+    let decoded: Vec<Result<u32, DecodeError>> =
+        FibReader::new(&data[..]).map(|n| n.unwrap()).collect();
+    let expected: Vec<Result<u32, DecodeError>> = fib_decode_bytes(&data).collect();
+    assert_eq!(expected, decoded);
+    assert!(decoded.last().unwrap().is_err());
+}
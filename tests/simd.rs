@@ -0,0 +1,101 @@
+#![cfg(feature = "simd")]
+
+use fibonacci_codec::{
+    fib_decode_bytes, fib_decode_bytes_with_order, BitOrder, Decode, DecodeError, Encode,
+    FibDecoder, IncrementalDecode,
+};
+use proptest::prelude::*;
+use std::fmt::Debug;
+use std::num::NonZeroU64;
+
+/// Checks that a `FibDecoder` fed `words`, or their bytes, gives the
+/// same results as the scalar bit-at-a-time decoder.
+fn decodes_like_scalar<T>(words: &[u64], order: BitOrder) -> Result<(), TestCaseError>
+where
+    T: Decode + IncrementalDecode + Debug + PartialEq,
+{
+    let bytes: Vec<u8> = words
+        .iter()
+        .flat_map(|word| match order {
+            BitOrder::MsbFirst => word.to_be_bytes(),
+            BitOrder::LsbFirst => word.to_le_bytes(),
+        })
+        .collect();
+    let expected: Vec<Result<T, DecodeError>> =
+        fib_decode_bytes_with_order(&bytes, order).collect();
+
+    let mut decoder = FibDecoder::<T>::with_bit_order(order);
+    let mut decoded: Vec<Result<T, DecodeError>> = decoder.feed_words(words).collect();
+    if let Err(e) = decoder.finish() {
+        decoded.push(Err(e));
+    }
+    prop_assert_eq!(&expected, &decoded);
+
+    let mut decoded: Vec<Result<T, DecodeError>> = decoder.feed(&bytes).collect();
+    if let Err(e) = decoder.finish() {
+        decoded.push(Err(e));
+    }
+    prop_assert_eq!(&expected, &decoded);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn decodes_words_like_scalar(ref words in proptest::collection::vec(
+                                     // Sparse words make for long code words, which overflow;
+                                     // runs of set bits for many short ones:
+                                     prop_oneof![any::<u64>(), Just(0_u64), Just(u64::MAX),
+                                                 any::<[u64; 3]>().prop_map(|w| w[0] & w[1] & w[2])],
+                                     0..40),
+                                 lsb_first in any::<bool>()) {
+        let order = if lsb_first { BitOrder::LsbFirst } else { BitOrder::MsbFirst };
+        decodes_like_scalar::<u8>(words, order)?;
+        decodes_like_scalar::<u16>(words, order)?;
+        decodes_like_scalar::<u64>(words, order)?;
+        decodes_like_scalar::<u128>(words, order)?;
+        decodes_like_scalar::<i32>(words, order)?;
+        decodes_like_scalar::<NonZeroU64>(words, order)?;
+    }
+
+    #[test]
+    fn roundtrips_words(ref input in proptest::collection::vec(any::<u64>().prop_filter("Values must be >0".to_owned(),
+                                                                                       |v| *v > 0),
+                                                               0..200)) {
+        let bytes = input.clone().fib_encode_bytes().expect("Expected an Ok result");
+        let words: Vec<u64> = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_be_bytes(word)
+            })
+            .collect();
+        // More than seven zero bits of padding in the last word are
+        // reported as a truncated code word, by both decoders:
+        let padded: Vec<u8> = words.iter().flat_map(|word| word.to_be_bytes()).collect();
+        let expected: Vec<Result<u64, DecodeError>> = fib_decode_bytes(&padded).collect();
+
+        let mut decoder = FibDecoder::<u64>::new();
+        let mut decoded: Vec<Result<u64, DecodeError>> = decoder.feed_words(&words).collect();
+        if let Err(e) = decoder.finish() {
+            decoded.push(Err(e));
+        }
+        prop_assert_eq!(&expected, &decoded);
+        let numbers: Vec<Result<u64, DecodeError>> = input.iter().map(|n| Ok(*n)).collect();
+        prop_assert_eq!(&numbers[..], &decoded[..input.len()]);
+    }
+}
+
+#[test]
+fn test_runs_across_lanes() {
+    // Code words of 1 ("11") that straddle the words' boundaries:
+    let input = vec![1_u32; 200];
+    let mut bits = vec![3_u32].fib_encode().unwrap();
+    bits.extend(input.clone().fib_encode().unwrap().iter());
+    let bytes = bits.to_bytes();
+    let mut decoder = FibDecoder::<u32>::new();
+    let decoded: Vec<u32> = decoder.feed(&bytes).map(|n| n.unwrap()).collect();
+    assert_eq!(3, decoded[0]);
+    assert_eq!(input, decoded[1..]);
+    assert_eq!(Ok(()), decoder.finish());
+}
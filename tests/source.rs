@@ -0,0 +1,142 @@
+use bit_vec::BitVec;
+use fibonacci_codec::{
+    fib_decode_source, BitSink, BitSource, Decode, DecodeError, ElementEncodeError, Encode,
+    EncodeError, EncodeOne,
+};
+use std::collections::VecDeque;
+
+/// A fixed-capacity ring buffer of bits.
+struct Ring {
+    bits: VecDeque<bool>,
+    capacity: usize,
+}
+
+impl Ring {
+    fn new(capacity: usize) -> Ring {
+        Ring {
+            bits: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+}
+
+impl BitSink for Ring {
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn push_zeros(&mut self, n: usize) {
+        assert!(self.bits.len() + n <= self.capacity, "ring buffer full");
+        self.bits.extend(std::iter::repeat_n(false, n));
+    }
+
+    fn set_bit(&mut self, i: usize) {
+        self.bits[i] = true;
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.bits.truncate(len);
+    }
+}
+
+impl BitSource for Ring {
+    fn next_bit(&mut self) -> Option<bool> {
+        self.bits.pop_front()
+    }
+}
+
+#[test]
+fn test_custom_sink_and_source() {
+    let mut ring = Ring::new(128);
+    vec![1_u32, 50, 3003].fib_encode_into(&mut ring).unwrap();
+    (-4_i16).fib_encode_into(&mut ring).unwrap();
+    assert_eq!(
+        vec![1_u32, 50, 3003].fib_encode().unwrap().len() + 6,
+        ring.len()
+    );
+
+    assert_eq!(Some(Ok(1_u32)), u32::fib_decode_next(&mut ring));
+    assert_eq!(Some(Ok(50_u64)), u64::fib_decode_next(&mut ring));
+    // Refill the ring while reading from it:
+    7_u8.fib_encode_into(&mut ring).unwrap();
+    assert_eq!(Some(Ok(3003_u16)), u16::fib_decode_next(&mut ring));
+    assert_eq!(Some(Ok(-4)), i16::fib_decode_next(&mut ring));
+    let rest: Vec<Result<u8, DecodeError>> = fib_decode_source(ring).collect();
+    assert_eq!(vec![Ok(7)], rest);
+}
+
+#[test]
+fn test_custom_sink_errors() {
+    let mut ring = Ring::new(16);
+    assert_eq!(
+        Err(ElementEncodeError {
+            index: 1,
+            error: EncodeError::ValueTooSmall(0)
+        }),
+        vec![2_u8, 0].fib_encode_into(&mut ring)
+    );
+    assert_eq!(3, ring.len());
+}
+
+#[test]
+fn test_dyn_source() {
+    let bits = vec![14_u64, 65].fib_encode().unwrap();
+    let mut iter = bits.iter();
+    let source: &mut dyn BitSource = &mut iter;
+    assert_eq!(Some(Ok(14)), u64::fib_decode_next(source));
+    assert_eq!(Some(Ok(65)), u8::fib_decode_next(source));
+    assert_eq!(None, u8::fib_decode_next(source));
+}
+
+#[test]
+fn test_next_word() {
+    // "11", "011", "0011"
+    let bits = vec![1_u8, 2, 3].fib_encode().unwrap();
+    let mut iter = bits.iter();
+    assert_eq!((0b11011, 5), iter.next_word(5));
+    assert_eq!((0, 0), iter.next_word(0));
+    assert_eq!((0b1100, 4), iter.next_word(64));
+    assert_eq!((0, 0), iter.next_word(8));
+}
+
+/// A source that only supports reading words.
+struct WordsOnly {
+    bits: BitVec,
+    pos: usize,
+}
+
+impl BitSource for WordsOnly {
+    fn next_bit(&mut self) -> Option<bool> {
+        panic!("bits should be read a word at a time");
+    }
+
+    fn next_word(&mut self, n: u32) -> (u64, u32) {
+        let mut word = 0;
+        let mut count = 0;
+        while count < n && self.pos < self.bits.len() {
+            word |= (self.bits[self.pos] as u64) << count;
+            self.pos += 1;
+            count += 1;
+        }
+        (word, count)
+    }
+}
+
+#[test]
+fn test_decode_source_reads_words() {
+    let input: Vec<u64> = (1..1000).map(|n| n * n * n).collect();
+    let source = WordsOnly {
+        bits: input.clone().fib_encode().unwrap(),
+        pos: 0,
+    };
+    let decoded: Vec<Result<u64, DecodeError>> = fib_decode_source(source).collect();
+    assert_eq!(input.into_iter().map(Ok).collect::<Vec<_>>(), decoded);
+
+    // A cut-off code word is still reported:
+    let mut bits = vec![3003_u64].fib_encode().unwrap();
+    bits.truncate(bits.len() - 1);
+    let source = WordsOnly { bits, pos: 0 };
+    let decoded: Vec<Result<u64, DecodeError>> = fib_decode_source(source).collect();
+    assert_eq!(1, decoded.len());
+    assert!(decoded[0].is_err());
+}
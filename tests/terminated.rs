@@ -0,0 +1,142 @@
+use bit_vec::BitVec;
+use fibonacci_codec::{
+    fib_decode_terminated, ByteBits, DecodeError, ElementEncodeError, Encode, EncodeError,
+};
+use std::num::NonZeroU8;
+
+fn to_bits(slice: BitVec) -> Vec<u8> {
+    slice.iter().map(|b| if b { 1 } else { 0 } as u8).collect()
+}
+
+#[test]
+fn test_known_vectors() {
+    // Each number's code word is that of the next larger one:
+    assert_eq!(
+        vec![
+            0, 1, 1, // 1 (as 2)
+            1, 0, 1, 1, // 3 (as 4)
+            1, 1, // terminator
+        ],
+        to_bits(vec![1_u8, 3].fib_encode_terminated().unwrap())
+    );
+    assert_eq!(
+        vec![1, 1],
+        to_bits(Vec::<u8>::new().fib_encode_terminated().unwrap())
+    );
+}
+
+#[test]
+fn test_roundtrip() {
+    let input: Vec<u8> = vec![1, 2, u8::MAX - 1, u8::MAX];
+    let bits = input.clone().fib_encode_terminated().unwrap();
+    let decoded: Vec<u8> = fib_decode_terminated(&bits).map(|n| n.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<u128> = vec![1, u128::MAX];
+    let bits = input.clone().fib_encode_terminated().unwrap();
+    let decoded: Vec<u128> = fib_decode_terminated(&bits).map(|n| n.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    let input: Vec<i128> = vec![0, -1, 1, i128::MIN, i128::MAX];
+    let bits = input.clone().fib_encode_terminated().unwrap();
+    let decoded: Vec<i128> = fib_decode_terminated(&bits).map(|n| n.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    // Their code words, up to that of u16::MAX + 2, are looked up:
+    let input: Vec<i16> = (i16::MIN..=i16::MAX).collect();
+    let bits = input.clone().fib_encode_terminated().unwrap();
+    let decoded: Vec<i16> = fib_decode_terminated(&bits).map(|n| n.unwrap()).collect();
+    assert_eq!(input, decoded);
+
+    let input = vec![NonZeroU8::MIN, NonZeroU8::MAX];
+    let Ok(bits) = input.clone().fib_encode_terminated();
+    let decoded: Vec<NonZeroU8> = fib_decode_terminated(&bits).map(|n| n.unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_zero() {
+    assert_eq!(
+        Err(ElementEncodeError {
+            index: 1,
+            error: EncodeError::ValueTooSmall(0)
+        }),
+        vec![1_u32, 0].fib_encode_terminated()
+    );
+}
+
+#[test]
+fn test_stops_at_terminator() {
+    let mut bits = vec![7_i16, -7].fib_encode_terminated().unwrap();
+    bits.extend(vec![1_u64, 2, 3].fib_encode().unwrap());
+    let mut decoded = fib_decode_terminated::<i16, _>(&bits);
+    assert_eq!(Some(Ok(7)), decoded.next());
+    assert_eq!(Some(Ok(-7)), decoded.next());
+    assert!(!decoded.is_terminated());
+    assert_eq!(None, decoded.next());
+    assert!(decoded.is_terminated());
+    assert_eq!(None, decoded.next());
+
+    let rest: Vec<u64> = fibonacci_codec::fib_decode(decoded.into_inner())
+        .map(|n| n.unwrap())
+        .collect();
+    assert_eq!(vec![1, 2, 3], rest);
+}
+
+#[test]
+fn test_concatenated_bytes() {
+    let mut bytes = vec![14_u32, 65].fib_encode_terminated().unwrap().to_bytes();
+    bytes.extend(vec![3_u32].fib_encode_terminated().unwrap().to_bytes());
+
+    let mut first = fib_decode_terminated::<u32, _>(ByteBits::new(&bytes));
+    let decoded: Vec<u32> = first.by_ref().map(|n| n.unwrap()).collect();
+    assert_eq!(vec![14, 65], decoded);
+    assert!(first.is_terminated());
+
+    let mut bits = first.into_inner();
+    bits.skip_to_byte_boundary();
+    let mut second = fib_decode_terminated::<u32, _>(bits);
+    let decoded: Vec<u32> = second.by_ref().map(|n| n.unwrap()).collect();
+    assert_eq!(vec![3], decoded);
+    assert!(second.is_terminated());
+}
+
+#[test]
+fn test_missing_terminator() {
+    let mut bits = vec![5_u16, 8].fib_encode_terminated().unwrap();
+    bits.truncate(bits.len() - 2);
+    let mut decoded = fib_decode_terminated::<u16, _>(&bits);
+    let values: Vec<Result<u16, DecodeError>> = decoded.by_ref().collect();
+    assert_eq!(vec![Ok(5), Ok(8)], values);
+    assert!(!decoded.is_terminated());
+
+    // A lone set bit at the end of the input:
+    bits.push(true);
+    let values: Vec<Result<u16, DecodeError>> = fib_decode_terminated(&bits).collect();
+    assert_eq!(
+        vec![
+            Ok(5),
+            Ok(8),
+            Err(DecodeError::Truncated {
+                bit_pos: 0,
+                bits_pending: 1
+            })
+        ],
+        values
+    );
+}
+
+#[test]
+fn test_overflow() {
+    // 255 is shifted to 256, which still decodes as a u8:
+    let bits = vec![255_u64, 256, 1].fib_encode_terminated().unwrap();
+    let decoded: Vec<Result<u8, DecodeError>> = fib_decode_terminated(&bits).collect();
+    assert_eq!(
+        vec![
+            Ok(255),
+            Err(DecodeError::ConstructionOverflow { bit_pos: 11 }),
+            Ok(1)
+        ],
+        decoded
+    );
+}
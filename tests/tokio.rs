@@ -0,0 +1,280 @@
+#![cfg(feature = "tokio")]
+
+use bytes::BytesMut;
+use fibonacci_codec::{
+    fib_decode_bytes, AsyncFibReader, AsyncFibWriter, BitOrder, ByteSink, DecodeError, Encode,
+    FibCodec, FibWriter,
+};
+use futures::{SinkExt, StreamExt};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{duplex, AsyncWrite, AsyncWriteExt};
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+#[tokio::test]
+async fn test_framed_duplex() {
+    let batches: Vec<Vec<u64>> = vec![
+        vec![1, 2, 14, 65, u64::MAX],
+        vec![],
+        vec![3003],
+        (1..1000).map(|n| n * 37).collect(),
+    ];
+    // A tiny buffer splits code words across many chunks:
+    let (client, server) = duplex(3);
+    let sent = batches.clone();
+    let send = async move {
+        let mut framed = FramedWrite::new(client, FibCodec::<u64>::new());
+        for batch in sent {
+            framed.send(batch).await.unwrap();
+        }
+    };
+    let receive = async move {
+        FramedRead::new(server, FibCodec::<u64>::new())
+            .map(|frame| frame.unwrap())
+            .collect::<Vec<Vec<u64>>>()
+            .await
+    };
+    let ((), received) = tokio::join!(send, receive);
+    assert_eq!(batches, received);
+}
+
+#[test]
+fn test_decode_byte_by_byte() {
+    let mut codec = FibCodec::<i32>::with_bit_order(BitOrder::LsbFirst);
+    let mut encoded = BytesMut::new();
+    codec
+        .encode(vec![-1_i32, 0, i32::MIN], &mut encoded)
+        .unwrap();
+    codec.encode(&[i32::MAX][..], &mut encoded).unwrap();
+    // Each frame is padded to a byte:
+    let mut frame = ByteSink::with_bit_order(BitOrder::LsbFirst);
+    vec![-1_i32, 0, i32::MIN]
+        .fib_encode_terminated_into(&mut frame)
+        .unwrap();
+    assert_eq!(frame.as_bytes(), &encoded[..frame.as_bytes().len()]);
+
+    let mut decoder = FibCodec::<i32>::with_bit_order(BitOrder::LsbFirst);
+    let mut src = BytesMut::new();
+    let mut frames = vec![];
+    for byte in encoded.iter() {
+        src.extend_from_slice(&[*byte]);
+        while let Some(frame) = decoder.decode(&mut src).unwrap() {
+            frames.push(frame);
+        }
+    }
+    assert_eq!(vec![vec![-1, 0, i32::MIN], vec![i32::MAX]], frames);
+    assert!(src.is_empty());
+    assert_eq!(None, decoder.decode_eof(&mut src).unwrap());
+}
+
+#[test]
+fn test_codec_errors() {
+    let mut codec = FibCodec::<u8>::new();
+    let mut dst = BytesMut::new();
+    let err = codec.encode(vec![1_u8, 0], &mut dst).unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    assert!(dst.is_empty());
+
+    // 256 overflows a u8:
+    let mut src = BytesMut::from(&vec![256_u16].fib_encode_terminated().unwrap().to_bytes()[..]);
+    let err = codec.decode(&mut src).unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidData, err.kind());
+
+    // A frame without its terminator:
+    let mut src = BytesMut::from(&vec![2_u8, 3].fib_encode().unwrap().to_bytes()[..]);
+    let mut codec = FibCodec::<u8>::new();
+    assert_eq!(None, codec.decode(&mut src).unwrap());
+    let err = codec.decode_eof(&mut src).unwrap_err();
+    assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+
+    // A code word that overflows a u8 and ends the stream exactly at a
+    // byte boundary is reported as such, rather than as a cut-off
+    // frame:
+    let bytes = vec![1000_u16].fib_encode().unwrap().to_bytes();
+    assert_eq!(2, bytes.len());
+    let mut src = BytesMut::from(&bytes[..]);
+    let mut codec = FibCodec::<u8>::new();
+    let err = codec.decode_eof(&mut src).unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    assert_eq!(None, codec.decode_eof(&mut src).unwrap());
+}
+
+#[test]
+fn test_decode_keeps_state() {
+    let batch: Vec<u128> = vec![u128::MAX, 1, u128::MAX - 1];
+    let mut encoded = BytesMut::new();
+    FibCodec::<u128>::new()
+        .encode(&batch[..], &mut encoded)
+        .unwrap();
+
+    // Each byte gets decoded as it arrives, rather than again with
+    // the next one:
+    let mut codec = FibCodec::<u128>::new();
+    let mut src = BytesMut::new();
+    let (last, bytes) = encoded.split_last().unwrap();
+    for byte in bytes {
+        src.extend_from_slice(&[*byte]);
+        assert_eq!(None, codec.decode(&mut src).unwrap());
+        assert!(src.is_empty());
+    }
+    src.extend_from_slice(&[*last]);
+    assert_eq!(Some(batch), codec.decode(&mut src).unwrap());
+    assert_eq!(None, codec.decode_eof(&mut src).unwrap());
+}
+
+#[tokio::test]
+async fn test_async_writer_and_reader() {
+    let input: Vec<u32> = (1..20_000).map(|n| n * 13).collect();
+    let mut sync_writer = FibWriter::new(Vec::new());
+    sync_writer.extend(input.iter().copied());
+    let expected = sync_writer.finish().unwrap();
+
+    let (client, server) = duplex(5);
+    let sent = input.clone();
+    let send = async move {
+        let mut writer = AsyncFibWriter::new(client);
+        for n in sent {
+            writer.write_value(n).await.unwrap();
+        }
+        let mut client = writer.finish().await.unwrap();
+        client.shutdown().await.unwrap();
+    };
+    let receive = async move {
+        let mut reader = AsyncFibReader::<_, u32>::new(server);
+        let mut decoded = vec![];
+        while let Some(n) = reader.read_value().await.unwrap() {
+            decoded.push(n.unwrap());
+        }
+        decoded
+    };
+    let ((), decoded) = tokio::join!(send, receive);
+    assert_eq!(input, decoded);
+
+    let mut writer = AsyncFibWriter::new(Vec::new());
+    for n in input {
+        writer.write_value(n).await.unwrap();
+    }
+    assert_eq!(expected, writer.finish().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_async_bit_order() {
+    let input: Vec<u16> = vec![1, 50, 3003, u16::MAX];
+    let mut writer = AsyncFibWriter::with_bit_order(Vec::new(), BitOrder::LsbFirst);
+    for n in input.iter() {
+        writer.write_value(*n).await.unwrap();
+    }
+    let bytes = writer.finish().await.unwrap();
+    assert_eq!(
+        input
+            .clone()
+            .fib_encode_bytes_with_order(BitOrder::LsbFirst)
+            .unwrap(),
+        bytes
+    );
+
+    let mut reader = AsyncFibReader::<_, u16>::with_bit_order(&bytes[..], BitOrder::LsbFirst);
+    let mut decoded = vec![];
+    while let Some(n) = reader.read_value().await.unwrap() {
+        decoded.push(n.unwrap());
+    }
+    assert_eq!(input, decoded);
+}
+
+#[tokio::test]
+async fn test_async_errors() {
+    let mut writer = AsyncFibWriter::new(Vec::new());
+    writer.write_value(3_u8).await.unwrap();
+    let err = writer.write_value(0_u8).await.unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    assert_eq!(vec![0b0011_0000], writer.finish().await.unwrap());
+
+    // 256 overflows a u8; 4000 is cut off after its first byte:
+    let mut bytes = vec![256_u64, 3, 4000].fib_encode().unwrap().to_bytes();
+    bytes.pop();
+    let mut reader = AsyncFibReader::<_, u8>::new(&bytes[..]);
+    let mut decoded = vec![];
+    while let Some(n) = reader.read_value().await.unwrap() {
+        decoded.push(n);
+    }
+    let expected: Vec<Result<u8, DecodeError>> = fib_decode_bytes(&bytes).collect();
+    assert_eq!(expected, decoded);
+    assert!(matches!(
+        decoded[0],
+        Err(DecodeError::ConstructionOverflow { .. })
+    ));
+    assert_eq!(Ok(3), decoded[1]);
+    assert!(matches!(
+        decoded[2],
+        Err(DecodeError::FibonacciElementOverflow { .. })
+    ));
+
+    let mut bytes = vec![4000_u16].fib_encode().unwrap().to_bytes();
+    bytes.pop();
+    let mut reader = AsyncFibReader::<_, u16>::new(&bytes[..]);
+    assert_eq!(
+        Some(Err(DecodeError::Truncated {
+            bit_pos: 3,
+            bits_pending: 16
+        })),
+        reader.read_value().await.unwrap()
+    );
+    assert_eq!(None, reader.read_value().await.unwrap());
+}
+
+/// An async writer that fails once, after accepting `fail_at` bytes,
+/// and accepts everything afterwards.
+#[derive(Debug)]
+struct FlakyWriter {
+    bytes: Vec<u8>,
+    fail_at: Option<usize>,
+}
+
+impl AsyncWrite for FlakyWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let n = match self.fail_at {
+            Some(at) if self.bytes.len() >= at => {
+                self.fail_at = None;
+                return Poll::Ready(Err(io::Error::new(io::ErrorKind::BrokenPipe, "flaky")));
+            }
+            Some(at) => buf.len().min(at - self.bytes.len()),
+            None => buf.len(),
+        };
+        self.bytes.extend_from_slice(&buf[..n]);
+        Poll::Ready(Ok(n))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn test_async_retry_after_io_error() {
+    let input: Vec<u32> = (1..100_000).map(|n| n * 7).collect();
+    let mut writer = AsyncFibWriter::new(FlakyWriter {
+        bytes: Vec::new(),
+        fail_at: Some(1000),
+    });
+    let mut failures = 0;
+    for n in input.iter() {
+        while let Err(e) = writer.write_value(*n).await {
+            assert_eq!(io::ErrorKind::BrokenPipe, e.kind());
+            failures += 1;
+        }
+    }
+    assert_eq!(1, failures);
+    // Neither the bytes written before the error nor the value that
+    // got retried are written twice:
+    let bytes = writer.finish().await.unwrap().bytes;
+    assert_eq!(input.fib_encode().unwrap().to_bytes(), bytes);
+}
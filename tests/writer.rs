@@ -0,0 +1,128 @@
+use fibonacci_codec::{fib_decode_bytes, Encode, FibWriter};
+use std::io::{self, Write};
+use std::num::NonZeroU64;
+
+#[test]
+fn test_same_as_to_bytes() {
+    let input: Vec<u32> = (1..100_000).map(|n| n * 7).collect();
+    let mut writer = FibWriter::new(Vec::new());
+    for n in input.iter() {
+        writer.write_value(*n).unwrap();
+    }
+    let bytes = writer.finish().unwrap();
+    assert_eq!(input.clone().fib_encode().unwrap().to_bytes(), bytes);
+
+    let decoded: Vec<u32> = fib_decode_bytes(&bytes).map(|n| n.unwrap()).collect();
+    assert_eq!(input, decoded);
+}
+
+#[test]
+fn test_extend() {
+    let input: Vec<i64> = vec![0, -1, i64::MIN, i64::MAX, 3];
+    let mut writer = FibWriter::new(Vec::new());
+    writer.extend(input.clone());
+    assert_eq!(
+        input.fib_encode().unwrap().to_bytes(),
+        writer.finish().unwrap()
+    );
+
+    let mut writer = FibWriter::new(Vec::new());
+    writer.extend(vec![NonZeroU64::MIN, NonZeroU64::MAX]);
+    assert_eq!(
+        vec![1_u64, u64::MAX].fib_encode().unwrap().to_bytes(),
+        writer.finish().unwrap()
+    );
+}
+
+#[test]
+fn test_empty() {
+    let writer = FibWriter::new(Vec::new());
+    assert_eq!(Vec::<u8>::new(), writer.finish().unwrap());
+}
+
+#[test]
+fn test_encode_error() {
+    let mut writer = FibWriter::new(Vec::new());
+    writer.write_value(1_u8).unwrap();
+    let err = writer.write_value(0_u8).unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    writer.write_value(2_u8).unwrap();
+    assert_eq!(
+        vec![1_u8, 2].fib_encode().unwrap().to_bytes(),
+        writer.finish().unwrap()
+    );
+
+    let mut writer = FibWriter::new(Vec::new());
+    writer.extend(vec![1_u8, 0, 2]);
+    let err = writer.finish().unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+}
+
+#[derive(Debug)]
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_io_error() {
+    let mut writer = FibWriter::new(FailingWriter);
+    writer.write_value(1_u32).unwrap();
+    let err = writer.finish().unwrap_err();
+    assert_eq!(io::ErrorKind::BrokenPipe, err.kind());
+}
+
+/// A writer that fails once, after accepting `fail_at` bytes, and
+/// accepts everything afterwards.
+#[derive(Debug)]
+struct FlakyWriter {
+    bytes: Vec<u8>,
+    fail_at: Option<usize>,
+}
+
+impl Write for FlakyWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = match self.fail_at {
+            Some(at) if self.bytes.len() >= at => {
+                self.fail_at = None;
+                return Err(io::Error::new(io::ErrorKind::BrokenPipe, "flaky"));
+            }
+            Some(at) => buf.len().min(at - self.bytes.len()),
+            None => buf.len(),
+        };
+        self.bytes.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_retry_after_io_error() {
+    let input: Vec<u32> = (1..100_000).map(|n| n * 7).collect();
+    let mut writer = FibWriter::new(FlakyWriter {
+        bytes: Vec::new(),
+        fail_at: Some(1000),
+    });
+    let mut failures = 0;
+    for n in input.iter() {
+        while let Err(e) = writer.write_value(*n) {
+            assert_eq!(io::ErrorKind::BrokenPipe, e.kind());
+            failures += 1;
+        }
+    }
+    assert_eq!(1, failures);
+    // Neither the bytes written before the error nor the value that
+    // got retried are written twice:
+    let bytes = writer.finish().unwrap().bytes;
+    assert_eq!(input.fib_encode().unwrap().to_bytes(), bytes);
+}